@@ -0,0 +1,237 @@
+use bon::bon;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::types::{
+	enums::{CraftingActionEnum, FailCause, StepState},
+	structs::{Craft, CrafterStats, Ingredient},
+	traits::CraftingAction,
+	Simulation, StateKey,
+};
+
+/// Computes the exact probability distribution of a rotation's outcome,
+/// branching over every condition transition and success roll.
+///
+/// Branches are merged once they can no longer be told apart by the steps left, which
+/// leaves out how they got there: rotations whose successes can come in any order
+/// don't double the branches at every roll.
+pub struct ExactEvaluator {
+	simulation: Simulation,
+	safe: bool,
+}
+
+#[bon]
+impl ExactEvaluator {
+	#[builder]
+	pub fn new(
+		recipe: Craft,
+		crafter_stats: CrafterStats,
+		actions: Vec<CraftingActionEnum>,
+		hq_ingredients: Option<Vec<Ingredient>>,
+		safe: Option<bool>,
+	) -> Self {
+		let simulation = Simulation::builder()
+			.recipe(recipe)
+			.crafter_stats(crafter_stats)
+			.actions(actions)
+			.maybe_hq_ingredients(hq_ingredients)
+			.build();
+
+		Self {
+			simulation,
+			safe: safe.unwrap_or(false),
+		}
+	}
+
+	pub fn run(&self) -> ExactResult {
+		let mut branches = vec![(self.simulation.clone(), 1.0)];
+		let mut peak_branches = branches.len();
+		for (i, action) in self.simulation.actions.iter().enumerate() {
+			let mut next_branches = Branches::default();
+			for (sim, probability) in branches {
+				for (roll, roll_probability) in Self::roll_outcomes(&sim, action) {
+					let mut sim = sim.clone();
+					let result = sim.run_step(action, i, false, self.safe, usize::MAX, Some(roll));
+					sim.steps.push(result);
+
+					let probability = probability * roll_probability;
+					if Simulation::ticks_state(action) {
+						for (state, state_probability) in sim.next_state_probabilities() {
+							let mut next = sim.clone();
+							next.override_state(state);
+							next_branches.insert(next, probability * state_probability);
+						}
+					} else {
+						next_branches.insert(sim, probability);
+					}
+				}
+			}
+			branches = next_branches.branches;
+			peak_branches = peak_branches.max(branches.len());
+		}
+
+		let mut outcomes: HashMap<(u32, bool, Option<FailCause>), ExactOutcome> = HashMap::new();
+		for (sim, probability) in branches {
+			let result = sim.into_result();
+			outcomes
+				.entry((result.simulation.quality, result.success, result.fail_cause))
+				.or_insert(ExactOutcome {
+					probability: 0.0,
+					quality: result.simulation.quality,
+					hq_percent: result.hq_percent,
+					success: result.success,
+					fail_cause: result.fail_cause,
+					collectable_tier: result.collectable_tier,
+				})
+				.probability += probability;
+		}
+		let mut outcomes: Vec<_> = outcomes.into_values().collect();
+		outcomes.sort_by(|a, b| {
+			a.quality
+				.cmp(&b.quality)
+				.then(a.success.cmp(&b.success))
+				.then(a.probability.total_cmp(&b.probability))
+		});
+		ExactResult {
+			outcomes,
+			peak_branches,
+		}
+	}
+
+	fn roll_outcomes(sim: &Simulation, action: &CraftingActionEnum) -> Vec<(u32, f64)> {
+		// the simulator succeeds when the success rate is at least a roll in 0..100,
+		// so a rate of N% actually covers N + 1 of the 100 possible rolls
+		let success_chance = ((action.get_success_rate(sim) + 1).min(100)) as f64 / 100.0;
+		if success_chance >= 1.0 {
+			vec![(0, 1.0)]
+		} else {
+			vec![(0, success_chance), (999, 1.0 - success_chance)]
+		}
+	}
+}
+
+#[derive(Default)]
+struct Branches {
+	branches: Vec<(Simulation, f64)>,
+	indices: HashMap<BranchKey, usize>,
+}
+
+impl Branches {
+	fn insert(&mut self, sim: Simulation, probability: f64) {
+		if probability <= 0.0 {
+			return;
+		}
+		let key = BranchKey::from(&sim);
+		match self.indices.get(&key) {
+			Some(&ix) => self.branches[ix].1 += probability,
+			None => {
+				self.indices.insert(key, self.branches.len());
+				self.branches.push((sim, probability));
+			}
+		}
+	}
+}
+
+/// Everything about a branch that can influence the rest of the craft or its result.
+#[derive(Eq, Hash, PartialEq)]
+struct BranchKey {
+	state: StepState,
+	// the exact quality is part of the result, even past the recipe's maximum
+	quality: u32,
+	max_cp: u32,
+	success: Option<bool>,
+	safe: bool,
+	// the result reports the first cause a step failed for
+	fail_cause: Option<FailCause>,
+	craft: StateKey,
+}
+
+impl From<&Simulation> for BranchKey {
+	fn from(sim: &Simulation) -> Self {
+		Self {
+			state: sim.state(),
+			quality: sim.quality,
+			max_cp: sim.max_cp,
+			success: sim.success,
+			safe: sim.safe,
+			fail_cause: sim.steps.iter().find_map(|step| step.fail_cause),
+			craft: StateKey::from(sim),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct ExactOutcome {
+	pub probability: f64,
+	pub quality: u32,
+	pub hq_percent: u32,
+	pub success: bool,
+	pub fail_cause: Option<FailCause>,
+	pub collectable_tier: Option<usize>,
+}
+
+#[derive(Clone, Default)]
+pub struct ExactResult {
+	/// Every distinct final outcome, sorted by quality.
+	pub outcomes: Vec<ExactOutcome>,
+	/// The most branches followed at once, after merging.
+	pub peak_branches: usize,
+}
+
+impl ExactResult {
+	pub fn success_probability(&self) -> f64 {
+		self.outcomes
+			.iter()
+			.filter(|outcome| outcome.success)
+			.map(|outcome| outcome.probability)
+			.sum()
+	}
+
+	pub fn quality_distribution(&self) -> BTreeMap<u32, f64> {
+		let mut distribution = BTreeMap::new();
+		for outcome in &self.outcomes {
+			*distribution.entry(outcome.quality).or_default() += outcome.probability;
+		}
+		distribution
+	}
+
+	pub fn hq_percent_distribution(&self) -> BTreeMap<u32, f64> {
+		let mut distribution = BTreeMap::new();
+		for outcome in &self.outcomes {
+			*distribution.entry(outcome.hq_percent).or_default() += outcome.probability;
+		}
+		distribution
+	}
+
+	/// Probability of ending at each collectable tier; the rest reach no tier.
+	pub fn collectable_tier_distribution(&self) -> BTreeMap<usize, f64> {
+		let mut distribution = BTreeMap::new();
+		for outcome in &self.outcomes {
+			if let Some(tier) = outcome.collectable_tier {
+				*distribution.entry(tier).or_default() += outcome.probability;
+			}
+		}
+		distribution
+	}
+
+	pub fn expected_quality(&self) -> f64 {
+		self.outcomes
+			.iter()
+			.map(|outcome| outcome.quality as f64 * outcome.probability)
+			.sum()
+	}
+
+	pub fn expected_hq_percent(&self) -> f64 {
+		self.outcomes
+			.iter()
+			.map(|outcome| outcome.hq_percent as f64 * outcome.probability)
+			.sum()
+	}
+
+	pub fn fail_cause_probability(&self, fail_cause: FailCause) -> f64 {
+		self.outcomes
+			.iter()
+			.filter(|outcome| outcome.fail_cause == Some(fail_cause))
+			.map(|outcome| outcome.probability)
+			.sum()
+	}
+}
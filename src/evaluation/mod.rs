@@ -0,0 +1,5 @@
+mod monte_carlo;
+pub use monte_carlo::*;
+
+mod exact;
+pub use exact::*;
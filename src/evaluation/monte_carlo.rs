@@ -0,0 +1,219 @@
+use bon::bon;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::types::{
+	enums::{CraftingActionEnum, FailCause},
+	structs::{Craft, CrafterStats, Ingredient, SimulationResult},
+	Simulation,
+};
+
+/// Runs a rotation many times with random conditions and success rolls,
+/// collecting statistics about the outcomes.
+pub struct MonteCarlo {
+	simulation: Simulation,
+	runs: usize,
+	threads: usize,
+	seed: Option<u64>,
+	safe: bool,
+}
+
+#[bon]
+impl MonteCarlo {
+	#[builder]
+	pub fn new(
+		recipe: Craft,
+		crafter_stats: CrafterStats,
+		actions: Vec<CraftingActionEnum>,
+		runs: usize,
+		hq_ingredients: Option<Vec<Ingredient>>,
+		threads: Option<usize>,
+		seed: Option<u64>,
+		safe: Option<bool>,
+	) -> Self {
+		let simulation = Simulation::builder()
+			.recipe(recipe)
+			.crafter_stats(crafter_stats)
+			.actions(actions)
+			.maybe_hq_ingredients(hq_ingredients)
+			.build();
+
+		Self {
+			simulation,
+			runs,
+			threads: threads.unwrap_or(1).max(1),
+			seed,
+			safe: safe.unwrap_or(false),
+		}
+	}
+
+	pub fn run(&self) -> MonteCarloResult {
+		// every run gets its own seed up front, so the outcome of a seeded
+		// evaluation doesn't depend on how the runs are split across threads
+		let mut seeder = match self.seed {
+			Some(seed) => StdRng::seed_from_u64(seed),
+			None => StdRng::from_entropy(),
+		};
+		let run_seeds: Vec<u64> = (0..self.runs).map(|_| seeder.gen()).collect();
+
+		let mut result = MonteCarloResult::default();
+		if run_seeds.is_empty() {
+			return result;
+		}
+
+		let chunk_size = (run_seeds.len() + self.threads - 1) / self.threads;
+		let partials: Vec<MonteCarloResult> = std::thread::scope(|scope| {
+			let handles: Vec<_> = run_seeds
+				.chunks(chunk_size)
+				.map(|seeds| {
+					let template = self.simulation.clone();
+					let safe = self.safe;
+					scope.spawn(move || {
+						let mut partial = MonteCarloResult::default();
+						for &seed in seeds {
+							let mut sim = template.clone();
+							sim.reseed(seed);
+							partial.record(&sim.start().safe(safe).run());
+						}
+						partial
+					})
+				})
+				.collect();
+			handles
+				.into_iter()
+				.map(|handle| handle.join().unwrap())
+				.collect()
+		});
+
+		partials
+			.into_iter()
+			.for_each(|partial| result.merge(partial));
+		result.qualities.sort_unstable();
+		result
+	}
+}
+
+#[derive(Clone, Default)]
+pub struct MonteCarloResult {
+	pub runs: usize,
+	pub successes: usize,
+	/// Number of runs that ended at each HQ percentage.
+	pub hq_percent_distribution: BTreeMap<u32, usize>,
+	/// Final quality of every run, sorted ascending.
+	pub qualities: Vec<u32>,
+	pub fail_causes: HashMap<FailCause, usize>,
+	/// Number of runs that ended at each collectable tier (see [`Craft::collectable_tiers`]).
+	pub collectable_tier_distribution: BTreeMap<usize, usize>,
+
+	total_hq_percent: u64,
+	total_cp: u64,
+	total_durability: i64,
+}
+
+impl MonteCarloResult {
+	fn record(&mut self, result: &SimulationResult) {
+		self.runs += 1;
+		if result.success {
+			self.successes += 1;
+		}
+		*self
+			.hq_percent_distribution
+			.entry(result.hq_percent)
+			.or_default() += 1;
+		self.qualities.push(result.simulation.quality);
+		if let Some(fail_cause) = result.fail_cause {
+			*self.fail_causes.entry(fail_cause).or_default() += 1;
+		}
+		if let Some(tier) = result.collectable_tier {
+			*self.collectable_tier_distribution.entry(tier).or_default() += 1;
+		}
+		self.total_hq_percent += result.hq_percent as u64;
+		self.total_cp += result.simulation.available_cp as u64;
+		self.total_durability += result.simulation.durability as i64;
+	}
+
+	fn merge(&mut self, other: MonteCarloResult) {
+		self.runs += other.runs;
+		self.successes += other.successes;
+		other
+			.hq_percent_distribution
+			.into_iter()
+			.for_each(|(hq, count)| *self.hq_percent_distribution.entry(hq).or_default() += count);
+		self.qualities.extend(other.qualities);
+		other
+			.fail_causes
+			.into_iter()
+			.for_each(|(cause, count)| *self.fail_causes.entry(cause).or_default() += count);
+		other
+			.collectable_tier_distribution
+			.into_iter()
+			.for_each(|(tier, count)| {
+				*self.collectable_tier_distribution.entry(tier).or_default() += count
+			});
+		self.total_hq_percent += other.total_hq_percent;
+		self.total_cp += other.total_cp;
+		self.total_durability += other.total_durability;
+	}
+
+	fn mean(&self, total: f64) -> f64 {
+		if self.runs == 0 {
+			0.0
+		} else {
+			total / self.runs as f64
+		}
+	}
+
+	pub fn success_rate(&self) -> f64 {
+		self.mean(self.successes as f64)
+	}
+
+	pub fn mean_hq_percent(&self) -> f64 {
+		self.mean(self.total_hq_percent as f64)
+	}
+
+	pub fn mean_quality(&self) -> f64 {
+		self.mean(self.qualities.iter().map(|&q| q as f64).sum())
+	}
+
+	pub fn mean_cp(&self) -> f64 {
+		self.mean(self.total_cp as f64)
+	}
+
+	pub fn mean_durability(&self) -> f64 {
+		self.mean(self.total_durability as f64)
+	}
+
+	/// Nearest-rank percentile of the final quality, with `percentile` in `0.0..=100.0`.
+	pub fn quality_percentile(&self, percentile: f64) -> u32 {
+		if self.qualities.is_empty() {
+			return 0;
+		}
+		let rank = (percentile.clamp(0.0, 100.0) / 100.0 * self.qualities.len() as f64).ceil();
+		self.qualities[(rank as usize).saturating_sub(1)]
+	}
+
+	/// Fraction of runs that ended with the given fail cause.
+	pub fn fail_cause_rate(&self, fail_cause: FailCause) -> f64 {
+		self.mean(self.fail_causes.get(&fail_cause).copied().unwrap_or(0) as f64)
+	}
+
+	/// Fraction of runs that ended at exactly the given collectable tier.
+	pub fn collectable_tier_rate(&self, tier: usize) -> f64 {
+		self.mean(
+			self.collectable_tier_distribution
+				.get(&tier)
+				.copied()
+				.unwrap_or(0) as f64,
+		)
+	}
+
+	/// Fraction of runs that reached at least the given collectable tier.
+	pub fn collectable_tier_at_least_rate(&self, tier: usize) -> f64 {
+		self.mean(
+			self.collectable_tier_distribution
+				.range(tier..)
+				.map(|(_, &count)| count)
+				.sum::<usize>() as f64,
+		)
+	}
+}
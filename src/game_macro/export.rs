@@ -0,0 +1,104 @@
+use bon::bon;
+
+use crate::types::{enums::CraftingActionEnum, traits::CraftingAction};
+
+/// The most lines the game allows in a single macro.
+pub const MAX_MACRO_LINES: usize = 15;
+
+/// Turns a rotation into in-game macro text, split into as many macros as needed.
+pub struct MacroExporter {
+	wait_padding: u32,
+	echo: bool,
+	echo_sound: Option<u32>,
+	macro_lock: bool,
+	final_wait: bool,
+	lines_per_macro: usize,
+}
+
+#[bon]
+impl MacroExporter {
+	/// * `wait_padding`: extra seconds added to every `<wait.N>`
+	/// * `echo`: end each macro with an `/echo` line, with `<se.N>` if `echo_sound` is set
+	/// * `macro_lock`: start each macro with `/macrolock`
+	/// * `final_wait`: whether the very last action still gets a `<wait.N>` (default `true`)
+	#[builder]
+	pub fn new(
+		wait_padding: Option<u32>,
+		echo: Option<bool>,
+		echo_sound: Option<u32>,
+		macro_lock: Option<bool>,
+		final_wait: Option<bool>,
+		lines_per_macro: Option<usize>,
+	) -> Self {
+		Self {
+			wait_padding: wait_padding.unwrap_or(0),
+			echo: echo.unwrap_or(false),
+			echo_sound,
+			macro_lock: macro_lock.unwrap_or(false),
+			final_wait: final_wait.unwrap_or(true),
+			lines_per_macro: lines_per_macro
+				.unwrap_or(MAX_MACRO_LINES)
+				.clamp(1, MAX_MACRO_LINES),
+		}
+	}
+
+	/// Returns the text of each macro, with lines separated by `\n`.
+	pub fn export(&self, rotation: &[CraftingActionEnum]) -> Vec<String> {
+		let reserved_lines = self.macro_lock as usize + self.echo as usize;
+		// always leave room for at least one action per macro
+		let actions_per_macro = self.lines_per_macro.saturating_sub(reserved_lines).max(1);
+		let macro_count = (rotation.len() + actions_per_macro - 1) / actions_per_macro;
+
+		rotation
+			.chunks(actions_per_macro)
+			.enumerate()
+			.map(|(macro_ix, chunk)| {
+				let is_final_macro = macro_ix + 1 == macro_count;
+				let mut lines = vec![];
+				if self.macro_lock {
+					lines.push("/macrolock".to_string());
+				}
+				for (ix, action) in chunk.iter().enumerate() {
+					let is_final_line = is_final_macro && ix + 1 == chunk.len();
+					lines.push(self.action_line(action, self.final_wait || !is_final_line));
+				}
+				if self.echo {
+					let text = if is_final_macro {
+						"Craft finished".to_string()
+					} else {
+						format!("Macro #{} finished", macro_ix + 1)
+					};
+					lines.push(match self.echo_sound {
+						Some(sound) => format!("/echo {} <se.{}>", text, sound),
+						None => format!("/echo {}", text),
+					});
+				}
+				lines.join("\n")
+			})
+			.collect()
+	}
+
+	fn action_line(&self, action: &CraftingActionEnum, with_wait: bool) -> String {
+		// Final Appraisal's buff is removed by clicking it off rather than with an action
+		let command = if matches!(action, CraftingActionEnum::RemoveFinalAppraisal(_)) {
+			"/statusoff \"Final Appraisal\"".to_string()
+		} else {
+			format!("/ac \"{}\"", action.get_name())
+		};
+		if with_wait {
+			format!(
+				"{} <wait.{}>",
+				command,
+				action.get_wait_duration() + self.wait_padding
+			)
+		} else {
+			command
+		}
+	}
+}
+
+impl Default for MacroExporter {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
@@ -0,0 +1,91 @@
+use std::fmt;
+
+use crate::types::{actions, enums::CraftingActionEnum};
+
+use super::{action_from_name, is_action_command};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnknownAction {
+	/// 1-based line number in the imported text.
+	pub line: usize,
+	pub name: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MacroImportError {
+	pub unknown_actions: Vec<UnknownAction>,
+}
+
+impl fmt::Display for MacroImportError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let messages: Vec<_> = self
+			.unknown_actions
+			.iter()
+			.map(|unknown| format!("line {}: unknown action \"{}\"", unknown.line, unknown.name))
+			.collect();
+		write!(f, "{}", messages.join("; "))
+	}
+}
+
+impl std::error::Error for MacroImportError {}
+
+/// Reads a rotation back from in-game macro text, possibly spanning several macros.
+///
+/// `/ac`, `/action` and localized action command lines (such as `/aktion`) are read with quoted or unquoted action names in any
+/// supported language, and `/statusoff` for Final Appraisal becomes
+/// [`RemoveFinalAppraisal`](actions::RemoveFinalAppraisal).
+/// Placeholders like `<wait.3>`, other commands (`/echo`, `/macrolock`, ...),
+/// blank lines and `//` or `#` comments are skipped.
+pub fn import_macro(text: &str) -> Result<Vec<CraftingActionEnum>, MacroImportError> {
+	let mut rotation = vec![];
+	let mut unknown_actions = vec![];
+
+	for (ix, line) in text.lines().enumerate() {
+		let line = line.trim();
+		if !line.starts_with('/') || line.starts_with("//") {
+			continue;
+		}
+		let (command, argument) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+		let name = action_argument(argument);
+
+		match command.to_lowercase().as_str() {
+			command if is_action_command(command) => match action_from_name(&name) {
+				Some(action) => rotation.push(action),
+				None => unknown_actions.push(UnknownAction {
+					line: ix + 1,
+					name,
+				}),
+			},
+			"/statusoff"
+				if action_from_name(&name) == Some(actions::FinalAppraisal.into()) =>
+			{
+				rotation.push(actions::RemoveFinalAppraisal.into());
+			}
+			_ => (),
+		}
+	}
+
+	if unknown_actions.is_empty() {
+		Ok(rotation)
+	} else {
+		Err(MacroImportError { unknown_actions })
+	}
+}
+
+/// Strips `<...>` placeholders and surrounding quotes from a command's argument.
+fn action_argument(argument: &str) -> String {
+	let mut name = String::new();
+	let mut in_placeholder = false;
+	for chr in argument.chars() {
+		match chr {
+			'<' => in_placeholder = true,
+			'>' if in_placeholder => in_placeholder = false,
+			_ if !in_placeholder => name.push(chr),
+			_ => (),
+		}
+	}
+	name.trim()
+		.trim_matches(|chr| matches!(chr, '"' | '“' | '”' | '「' | '」'))
+		.trim()
+		.to_string()
+}
@@ -0,0 +1,8 @@
+mod names;
+pub use names::*;
+
+mod export;
+pub use export::*;
+
+mod import;
+pub use import::*;
@@ -0,0 +1,186 @@
+use crate::types::{enums::CraftingActionEnum, traits::CraftingAction};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Language {
+	English,
+	Japanese,
+	German,
+	French,
+}
+
+impl Language {
+	pub const ALL: [Language; 4] = [
+		Language::English,
+		Language::Japanese,
+		Language::German,
+		Language::French,
+	];
+}
+
+/// The action's name as shown in the given client language.
+///
+/// Remove Final Appraisal isn't an in-game action (the buff is clicked off instead),
+/// so it keeps its English name in every language.
+pub fn localized_name(action: &CraftingActionEnum, language: Language) -> &'static str {
+	use CraftingActionEnum::*;
+
+	match language {
+		Language::English => action.get_name(),
+		Language::Japanese => match action {
+			BasicSynthesis(_) => "作業",
+			CarefulSynthesis(_) => "模範作業",
+			PrudentSynthesis(_) => "倹約作業",
+			RapidSynthesis(_) => "突貫作業",
+			Groundwork(_) => "下地作業",
+			MuscleMemory(_) => "確信",
+			IntensiveSynthesis(_) => "集中作業",
+			BasicTouch(_) => "加工",
+			StandardTouch(_) => "中級加工",
+			AdvancedTouch(_) => "上級加工",
+			HastyTouch(_) => "ヘイスティタッチ",
+			ByregotsBlessing(_) => "ビエルゴの祝福",
+			PreciseTouch(_) => "集中加工",
+			PrudentTouch(_) => "倹約加工",
+			TrainedEye(_) => "匠の早業",
+			PreparatoryTouch(_) => "下地加工",
+			Reflect(_) => "真価",
+			TrainedFinesse(_) => "匠の神業",
+			DaringTouch(_) => "デアリングタッチ",
+			RefinedTouch(_) => "洗練加工",
+			TricksOfTheTrade(_) => "秘訣",
+			MastersMend(_) => "マスターズメンド",
+			Manipulation(_) => "マニピュレーション",
+			ImmaculateMend(_) => "パーフェクトメンド",
+			WasteNot(_) => "倹約",
+			WasteNotII(_) => "長期倹約",
+			GreatStrides(_) => "グレートストライド",
+			Innovation(_) => "イノベーション",
+			Veneration(_) => "ヴェネレーション",
+			FinalAppraisal(_) => "最終確認",
+			QuickInnovation(_) => "クイックイノベーション",
+			TrainedPerfection(_) => "匠の絶技",
+			Observe(_) => "経過観察",
+			HeartAndSoul(_) => "一心不乱",
+			CarefulObservation(_) => "設計変更",
+			DelicateSynthesis(_) => "精密作業",
+			RemoveFinalAppraisal(_) => action.get_name(),
+		},
+		Language::German => match action {
+			BasicSynthesis(_) => "Bearbeiten",
+			CarefulSynthesis(_) => "Sorgfältige Bearbeitung",
+			PrudentSynthesis(_) => "Umsichtige Bearbeitung",
+			RapidSynthesis(_) => "Schnelle Bearbeitung",
+			Groundwork(_) => "Vorarbeit",
+			MuscleMemory(_) => "Motorisches Gedächtnis",
+			IntensiveSynthesis(_) => "Intensive Bearbeitung",
+			BasicTouch(_) => "Veredelung",
+			StandardTouch(_) => "Solide Veredelung",
+			AdvancedTouch(_) => "Höhere Veredelung",
+			HastyTouch(_) => "Hastige Veredelung",
+			ByregotsBlessing(_) => "Byregots Benediktion",
+			PreciseTouch(_) => "Präzise Veredelung",
+			PrudentTouch(_) => "Nachhaltige Veredelung",
+			TrainedEye(_) => "Flinke Hand",
+			PreparatoryTouch(_) => "Basisveredelung",
+			Reflect(_) => "Einkehr",
+			TrainedFinesse(_) => "Meisterliche Veredelung",
+			DaringTouch(_) => "Wagemutige Veredelung",
+			RefinedTouch(_) => "Raffinierte Veredelung",
+			TricksOfTheTrade(_) => "Kunstgriff",
+			MastersMend(_) => "Wiederherstellung",
+			Manipulation(_) => "Manipulation",
+			ImmaculateMend(_) => "Makellose Wiederherstellung",
+			WasteNot(_) => "Nachhaltigkeit",
+			WasteNotII(_) => "Nachhaltigkeit II",
+			GreatStrides(_) => "Große Schritte",
+			Innovation(_) => "Innovation",
+			Veneration(_) => "Ehrfurcht",
+			FinalAppraisal(_) => "Endkontrolle",
+			QuickInnovation(_) => "Schnelle Innovation",
+			TrainedPerfection(_) => "Meisterliche Perfektion",
+			Observe(_) => "Beobachten",
+			HeartAndSoul(_) => "Mit Leib und Seele",
+			CarefulObservation(_) => "Genaue Beobachtung",
+			DelicateSynthesis(_) => "Akribische Bearbeitung",
+			RemoveFinalAppraisal(_) => action.get_name(),
+		},
+		Language::French => match action {
+			BasicSynthesis(_) => "Travail de base",
+			CarefulSynthesis(_) => "Travail prudent",
+			PrudentSynthesis(_) => "Travail économe",
+			RapidSynthesis(_) => "Travail rapide",
+			Groundwork(_) => "Travail préparatoire",
+			MuscleMemory(_) => "Mémoire musculaire",
+			IntensiveSynthesis(_) => "Travail vigilant",
+			BasicTouch(_) => "Ouvrage de base",
+			StandardTouch(_) => "Ouvrage standard",
+			AdvancedTouch(_) => "Ouvrage avancé",
+			HastyTouch(_) => "Ouvrage hâtif",
+			ByregotsBlessing(_) => "Bénédiction de Byregot",
+			PreciseTouch(_) => "Ouvrage précis",
+			PrudentTouch(_) => "Ouvrage économe",
+			TrainedEye(_) => "Œil de l'expert",
+			PreparatoryTouch(_) => "Ouvrage préparatoire",
+			Reflect(_) => "Véritable valeur",
+			TrainedFinesse(_) => "Doigté du maître",
+			DaringTouch(_) => "Ouvrage audacieux",
+			RefinedTouch(_) => "Ouvrage raffiné",
+			TricksOfTheTrade(_) => "Ficelles du métier",
+			MastersMend(_) => "Réparation de maître",
+			Manipulation(_) => "Manipulation",
+			ImmaculateMend(_) => "Réparation immaculée",
+			WasteNot(_) => "Parcimonie",
+			WasteNotII(_) => "Parcimonie pérenne",
+			GreatStrides(_) => "Grands progrès",
+			Innovation(_) => "Innovation",
+			Veneration(_) => "Vénération",
+			FinalAppraisal(_) => "Examen final",
+			QuickInnovation(_) => "Innovation rapide",
+			TrainedPerfection(_) => "Perfection du maître",
+			Observe(_) => "Observation",
+			HeartAndSoul(_) => "Attention totale",
+			CarefulObservation(_) => "Observation minutieuse",
+			DelicateSynthesis(_) => "Travail minutieux",
+			RemoveFinalAppraisal(_) => action.get_name(),
+		},
+	}
+}
+
+/// The text commands that use an action in the given client language.
+///
+/// Every client also accepts the English commands.
+pub fn action_commands(language: Language) -> &'static [&'static str] {
+	match language {
+		Language::German => &["/aktion"],
+		Language::English | Language::Japanese | Language::French => &["/ac", "/action"],
+	}
+}
+
+/// Whether the macro command uses an action in any supported language, ignoring case.
+pub fn is_action_command(command: &str) -> bool {
+	let command = command.to_lowercase();
+	Language::ALL
+		.iter()
+		.any(|&language| action_commands(language).contains(&command.as_str()))
+}
+
+/// Finds the action with the given name in any supported language,
+/// ignoring case and the kind of apostrophe used.
+pub fn action_from_name(name: &str) -> Option<CraftingActionEnum> {
+	let name = normalize(name);
+	CraftingActionEnum::ALL.into_iter().find(|action| {
+		Language::ALL
+			.iter()
+			.any(|&language| normalize(localized_name(action, language)) == name)
+	})
+}
+
+fn normalize(name: &str) -> String {
+	name.split_whitespace()
+		.collect::<Vec<_>>()
+		.join(" ")
+		.replace(['’', '‘'], "'")
+		.replace('œ', "oe")
+		.replace('Œ', "Oe")
+		.to_lowercase()
+}
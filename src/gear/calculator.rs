@@ -0,0 +1,148 @@
+use bon::bon;
+use std::fmt;
+
+use crate::types::{
+	enums::CraftingJob,
+	structs::{CrafterLevels, CrafterStats, CraftingLevel},
+};
+
+use super::{BaseStatsTable, GearItem, GearStats, MeldRateTable};
+
+/// What a job's soul crystal adds once the crafter is a specialist of it.
+pub const SPECIALIST_BONUS: GearStats = GearStats {
+	craftsmanship: 20,
+	control: 20,
+	cp: 15,
+};
+
+/// Works out a crafter's stats from their level, gear and materia.
+#[derive(Clone)]
+pub struct StatCalculator {
+	base_stats: BaseStatsTable,
+	pub(crate) meld_rates: MeldRateTable,
+	job: CraftingJob,
+	level: CraftingLevel,
+	levels: CrafterLevels,
+	pub(crate) items: Vec<GearItem>,
+	specialist: bool,
+	splendorous: bool,
+}
+
+#[bon]
+impl StatCalculator {
+	/// * `base_stats`: the [built-in](BaseStatsTable::builtin) ones by default
+	/// * `meld_rates`: overmeld success rates, the [built-in](MeldRateTable::builtin)
+	///   ones by default
+	/// * `levels`: every job's level, all at `level` by default
+	/// * `specialist`: whether the job's soul crystal is equipped
+	#[builder]
+	pub fn new(
+		base_stats: Option<BaseStatsTable>,
+		meld_rates: Option<MeldRateTable>,
+		job: CraftingJob,
+		level: CraftingLevel,
+		levels: Option<CrafterLevels>,
+		items: Vec<GearItem>,
+		specialist: Option<bool>,
+		splendorous: Option<bool>,
+	) -> Self {
+		Self {
+			base_stats: base_stats.unwrap_or_else(|| BaseStatsTable::builtin().clone()),
+			meld_rates: meld_rates.unwrap_or_else(|| MeldRateTable::builtin().clone()),
+			job,
+			levels: levels.unwrap_or_else(|| CrafterLevels::from([level; 8])),
+			level,
+			items,
+			specialist: specialist.unwrap_or(false),
+			splendorous: splendorous.unwrap_or(false),
+		}
+	}
+
+	/// Base stats, gear and materia (within each item's caps) and the specialist bonus
+	/// added up.
+	pub fn total_stats(&self) -> Result<GearStats, GearError> {
+		let mut total = self
+			.base_stats
+			.get(self.level.into())
+			.ok_or(GearError::UnknownLevel(self.level))?;
+		for item in &self.items {
+			if let Some(slot) = item.invalid_meld(&self.meld_rates) {
+				return Err(GearError::InvalidMeld {
+					item_id: item.id,
+					slot,
+				});
+			}
+			total += item.total_stats();
+		}
+		if self.specialist {
+			total += SPECIALIST_BONUS;
+		}
+		Ok(total)
+	}
+
+	/// The crafter, ready to simulate with.
+	pub fn crafter_stats(&self) -> Result<CrafterStats, GearError> {
+		let total = self.total_stats()?;
+		Ok(CrafterStats {
+			job_id: self.job.class_job_id().unwrap_or(0),
+			craftsmanship: total.craftsmanship,
+			control: total.control,
+			cp: total.cp,
+			specialist: self.specialist,
+			splendorous: self.splendorous,
+			level: self.level,
+			levels: self.levels.clone(),
+		})
+	}
+
+	/// How many materia melding everything takes on average, failed overmelds
+	/// included, as (grade, materia) pairs sorted by grade.
+	pub fn expected_materia(&self) -> Result<Vec<(u32, f64)>, GearError> {
+		let mut expected: Vec<(u32, f64)> = vec![];
+		for item in &self.items {
+			let rates = item.meld_success_rates(&self.meld_rates);
+			for (slot, (materia, rate)) in item.materia.iter().zip(rates).enumerate() {
+				let rate = rate.ok_or(GearError::InvalidMeld {
+					item_id: item.id,
+					slot,
+				})?;
+				let attempts = 100.0 / rate as f64;
+				match expected
+					.iter_mut()
+					.find(|(grade, _)| *grade == materia.grade)
+				{
+					Some((_, count)) => *count += attempts,
+					None => expected.push((materia.grade, attempts)),
+				}
+			}
+		}
+		expected.sort_by_key(|&(grade, _)| grade);
+		Ok(expected)
+	}
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GearError {
+	/// The base stats table has no row for the level.
+	UnknownLevel(CraftingLevel),
+	/// A materia is in a slot it can't be melded into.
+	InvalidMeld { item_id: u32, slot: usize },
+}
+
+impl fmt::Display for GearError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			GearError::UnknownLevel(level) => {
+				write!(f, "no base stats for level {}", u8::from(*level))
+			}
+			GearError::InvalidMeld { item_id, slot } => write!(
+				f,
+				"item {}: materia can't be melded into slot {}",
+				item_id,
+				slot + 1
+			),
+		}
+	}
+}
+
+impl std::error::Error for GearError {}
@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+
+use super::{ConsumableTable, GearItem, Materia};
+
+/// The items, materia and consumables gearsets are resolved against, by item id.
+#[derive(Clone, Debug, Default)]
+pub struct GearCatalog {
+	items: HashMap<(u32, bool), GearItem>,
+	pub materia: HashMap<u32, Materia>,
+	pub consumables: ConsumableTable,
+}
+
+impl GearCatalog {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds an item, in the quality given by its `hq`; an item can be added in both.
+	/// Its materia are ignored.
+	pub fn insert_item(&mut self, item: GearItem) {
+		self.items.insert((item.id, item.hq), item);
+	}
+
+	pub fn insert_materia(&mut self, item_id: u32, materia: Materia) {
+		self.materia.insert(item_id, materia);
+	}
+
+	/// An item in the given quality. An HQ item only known in NQ is returned in NQ, as
+	/// for items that can't be HQ.
+	pub fn item(&self, item_id: u32, hq: bool) -> Option<&GearItem> {
+		self.items
+			.get(&(item_id, hq))
+			.or_else(|| self.items.get(&(item_id, false)).filter(|_| hq))
+	}
+}
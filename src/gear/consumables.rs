@@ -0,0 +1,291 @@
+use std::{cmp::Reverse, collections::HashMap, fs::File, io::Read, path::Path};
+
+use crate::recipes::{sheet::Sheet, RecipeDataError};
+use crate::types::{
+	enums::CraftingActionEnum,
+	structs::{Craft, CrafterStats},
+	Simulation,
+};
+
+use super::{GearStat, GearStats};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConsumableKind {
+	Food,
+	Medicine,
+}
+
+/// A stat raised by a percentage of its value, up to a fixed amount.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConsumableBonus {
+	pub stat: GearStat,
+	pub percent: u32,
+	pub max: u32,
+	pub hq_percent: u32,
+	pub hq_max: u32,
+}
+
+impl ConsumableBonus {
+	pub fn value(&self, stat_value: u32, hq: bool) -> u32 {
+		let (percent, max) = if hq {
+			(self.hq_percent, self.hq_max)
+		} else {
+			(self.percent, self.max)
+		};
+		(stat_value * percent / 100).min(max)
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Consumable {
+	pub id: u32,
+	pub name: String,
+	pub kind: ConsumableKind,
+	pub bonuses: Vec<ConsumableBonus>,
+	pub price: Option<u64>,
+	pub hq_price: Option<u64>,
+}
+
+impl Consumable {
+	/// What the consumable adds to the given stats.
+	pub fn bonus(&self, stats: GearStats, hq: bool) -> GearStats {
+		let mut bonus = GearStats::default();
+		for stat_bonus in &self.bonuses {
+			*bonus.get_mut(stat_bonus.stat) += stat_bonus.value(stats.get(stat_bonus.stat), hq);
+		}
+		bonus
+	}
+
+	pub fn price(&self, hq: bool) -> Option<u64> {
+		if hq {
+			self.hq_price
+		} else {
+			self.price
+		}
+	}
+}
+
+impl CrafterStats {
+	/// The stats with food and medicine, each NQ or HQ. Both bonuses are worked out from
+	/// the stats without any consumable, as in game.
+	pub fn with_consumables(
+		&self,
+		food: Option<&Consumable>,
+		medicine: Option<&Consumable>,
+		food_hq: bool,
+		medicine_hq: bool,
+	) -> CrafterStats {
+		let stats = GearStats {
+			craftsmanship: self.craftsmanship,
+			control: self.control,
+			cp: self.cp,
+		};
+		let mut total = stats;
+		for (consumable, hq) in food
+			.map(|food| (food, food_hq))
+			.into_iter()
+			.chain(medicine.map(|medicine| (medicine, medicine_hq)))
+		{
+			total += consumable.bonus(stats, hq);
+		}
+		CrafterStats {
+			craftsmanship: total.craftsmanship,
+			control: total.control,
+			cp: total.cp,
+			..self.clone()
+		}
+	}
+}
+
+/// The statuses given by food and by medicine, as in the Status sheet.
+const WELL_FED: u32 = 48;
+const MEDICATED: u32 = 49;
+
+/// Food and medicine available to crafters.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConsumableTable {
+	pub consumables: Vec<Consumable>,
+}
+
+impl ConsumableTable {
+	pub fn new(consumables: Vec<Consumable>) -> Self {
+		Self { consumables }
+	}
+
+	/// Loads `Item.csv`, `ItemAction.csv` and `ItemFood.csv` from a directory.
+	pub fn load(dir: impl AsRef<Path>) -> Result<Self, RecipeDataError> {
+		let dir = dir.as_ref();
+		Self::from_readers(
+			File::open(dir.join("Item.csv"))?,
+			File::open(dir.join("ItemAction.csv"))?,
+			File::open(dir.join("ItemFood.csv"))?,
+		)
+	}
+
+	/// Reads the food and medicine raising craftsmanship, control or CP. Items are
+	/// linked to their ItemFood row through their ItemAction, whose status tells food
+	/// (Well Fed) from medicine (Medicated). Prices are left unknown.
+	pub fn from_readers(
+		item: impl Read,
+		item_action: impl Read,
+		item_food: impl Read,
+	) -> Result<Self, RecipeDataError> {
+		let mut actions = HashMap::new();
+		for row in Sheet::read("ItemAction", item_action)?.rows() {
+			let kind = match row.u32(&["Data[0]"])? {
+				WELL_FED => ConsumableKind::Food,
+				MEDICATED => ConsumableKind::Medicine,
+				_ => continue,
+			};
+			actions.insert(row.u32(&["#", "key"])?, (kind, row.u32(&["Data[1]"])?));
+		}
+
+		let mut bonuses = HashMap::new();
+		for row in Sheet::read("ItemFood", item_food)?.rows() {
+			let mut food_bonuses = vec![];
+			for ix in 0..3 {
+				let stat = match row.u32(&[&format!("BaseParam[{}]", ix)])? {
+					70 => GearStat::Craftsmanship,
+					71 => GearStat::Control,
+					11 => GearStat::Cp,
+					_ => continue,
+				};
+				// crafting food and medicine only raise stats by a percentage
+				if !row.bool(&[&format!("IsRelative[{}]", ix)])? {
+					continue;
+				}
+				food_bonuses.push(ConsumableBonus {
+					stat,
+					percent: row.u32(&[&format!("Value[{}]", ix)])?,
+					max: row.u32(&[&format!("Max[{}]", ix)])?,
+					hq_percent: row
+						.u32(&[&format!("Value{{HQ}}[{}]", ix), &format!("ValueHQ[{}]", ix)])?,
+					hq_max: row.u32(&[&format!("Max{{HQ}}[{}]", ix), &format!("MaxHQ[{}]", ix)])?,
+				});
+			}
+			if !food_bonuses.is_empty() {
+				bonuses.insert(row.u32(&["#", "key"])?, food_bonuses);
+			}
+		}
+
+		let mut consumables = vec![];
+		for row in Sheet::read("Item", item)?.rows() {
+			let Some((kind, food)) = actions.get(&row.u32(&["ItemAction"])?) else {
+				continue;
+			};
+			let Some(bonuses) = bonuses.get(food) else {
+				continue;
+			};
+			consumables.push(Consumable {
+				id: row.u32(&["#", "key"])?,
+				name: row.str(&["Name", "Singular"])?.to_string(),
+				kind: *kind,
+				bonuses: bonuses.clone(),
+				price: None,
+				hq_price: None,
+			});
+		}
+		Ok(Self::new(consumables))
+	}
+
+	pub fn get(&self, id: u32) -> Option<&Consumable> {
+		self.consumables
+			.iter()
+			.find(|consumable| consumable.id == id)
+	}
+
+	pub fn get_by_name(&self, name: &str) -> Option<&Consumable> {
+		self.consumables
+			.iter()
+			.find(|consumable| consumable.name.eq_ignore_ascii_case(name))
+	}
+
+	pub fn food(&self) -> impl Iterator<Item = &Consumable> {
+		self.of_kind(ConsumableKind::Food)
+	}
+
+	pub fn medicine(&self) -> impl Iterator<Item = &Consumable> {
+		self.of_kind(ConsumableKind::Medicine)
+	}
+
+	fn of_kind(&self, kind: ConsumableKind) -> impl Iterator<Item = &Consumable> {
+		self.consumables
+			.iter()
+			.filter(move |consumable| consumable.kind == kind)
+	}
+
+	/// Simulates the rotation (linearly) with every combination of food and medicine,
+	/// none included, from the best to the worst.
+	///
+	/// Combinations that finish the craft come first, then the ones reaching more
+	/// quality (up to the recipe's maximum), then the cheapest; combinations with a
+	/// consumable of unknown price come after the priced ones.
+	pub fn rank(
+		&self,
+		recipe: &Craft,
+		crafter_stats: &CrafterStats,
+		actions: &[CraftingActionEnum],
+		food_hq: bool,
+		medicine_hq: bool,
+	) -> Vec<ConsumableRanking> {
+		let foods: Vec<_> = [None].into_iter().chain(self.food().map(Some)).collect();
+		let medicines: Vec<_> = [None]
+			.into_iter()
+			.chain(self.medicine().map(Some))
+			.collect();
+
+		let mut rankings = vec![];
+		for &food in &foods {
+			for &medicine in &medicines {
+				let stats = crafter_stats.with_consumables(food, medicine, food_hq, medicine_hq);
+				let result = Simulation::builder()
+					.recipe(recipe.clone())
+					.crafter_stats(stats.clone())
+					.actions(actions.to_vec())
+					.build()
+					.start()
+					.linear(true)
+					.run();
+				rankings.push(ConsumableRanking {
+					food: food.cloned(),
+					medicine: medicine.cloned(),
+					price: food
+						.map(|food| food.price(food_hq))
+						.into_iter()
+						.chain(medicine.map(|medicine| medicine.price(medicine_hq)))
+						.sum(),
+					crafter_stats: stats,
+					quality: result.simulation.quality.min(recipe.quality),
+					hq_percent: result.hq_percent,
+					success: result.success,
+				});
+			}
+		}
+		rankings.sort_by_key(|ranking| {
+			(
+				Reverse(ranking.success),
+				Reverse(ranking.quality),
+				ranking.price.is_none(),
+				ranking.price,
+			)
+		});
+		rankings
+	}
+}
+
+#[derive(Clone)]
+pub struct ConsumableRanking {
+	pub food: Option<Consumable>,
+	pub medicine: Option<Consumable>,
+	/// What the food and medicine cost together, `None` if either has no known price.
+	pub price: Option<u64>,
+	pub crafter_stats: CrafterStats,
+	/// Quality at the end of the rotation, capped at the recipe's maximum.
+	pub quality: u32,
+	pub hq_percent: u32,
+	pub success: bool,
+}
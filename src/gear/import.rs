@@ -0,0 +1,342 @@
+use bon::bon;
+use serde_json::Value;
+use std::{collections::HashMap, fmt, fs, path::Path};
+
+use crate::types::{
+	enums::CraftingJob,
+	structs::{CrafterLevels, CrafterStats, CraftingLevel},
+};
+
+use super::{BaseStatsTable, GearCatalog, GearError, MeldRateTable, StatCalculator};
+
+/// Teamcraft's equipment slots, in gearset order.
+const TEAMCRAFT_SLOTS: [&str; 13] = [
+	"mainHand", "offHand", "head", "chest", "gloves", "belt", "legs", "feet", "earRings",
+	"necklace", "bracelet", "ring1", "ring2",
+];
+
+/// Etro's equipment slots, with the suffix of the slot's key in `materia` for the slots
+/// that can hold the same item twice.
+const ETRO_SLOTS: [(&str, &str); 12] = [
+	("weapon", ""),
+	("offHand", ""),
+	("head", ""),
+	("body", ""),
+	("hands", ""),
+	("legs", ""),
+	("feet", ""),
+	("ears", ""),
+	("neck", ""),
+	("wrists", ""),
+	("fingerL", "L"),
+	("fingerR", "R"),
+];
+
+/// A gearset as exported, before its items are looked up.
+struct ExportedGearset {
+	job: u32,
+	level: Option<u8>,
+	/// (item id, HQ, materia item ids) of every equipped item.
+	items: Vec<(u32, bool, Vec<u32>)>,
+	specialist: bool,
+	food: Option<(u32, bool)>,
+	medicine: Option<(u32, bool)>,
+}
+
+/// The crafting gearsets of a crafter, one per job.
+#[derive(Clone)]
+pub struct ImportedGearsets {
+	pub stats: HashMap<CraftingJob, CrafterStats>,
+	/// The level of every job with a gearset, the others being 0.
+	pub levels: CrafterLevels,
+}
+
+/// Reads gearsets exported from Etro or teamcraft and works out their stats.
+///
+/// Items, materia and consumables are looked up in the catalog by item id; any
+/// missing fails the import with every unknown id. Gearsets of jobs that can't craft
+/// are skipped.
+pub struct GearsetImporter {
+	catalog: GearCatalog,
+	base_stats: BaseStatsTable,
+	meld_rates: MeldRateTable,
+	default_level: CraftingLevel,
+}
+
+#[bon]
+impl GearsetImporter {
+	/// * `base_stats`, `meld_rates`: the built-in ones by default, as in [`StatCalculator`]
+	/// * `default_level`: the level of gearsets that don't say, the level cap by default
+	#[builder]
+	pub fn new(
+		catalog: GearCatalog,
+		base_stats: Option<BaseStatsTable>,
+		meld_rates: Option<MeldRateTable>,
+		default_level: Option<CraftingLevel>,
+	) -> Self {
+		Self {
+			catalog,
+			base_stats: base_stats.unwrap_or_else(|| BaseStatsTable::builtin().clone()),
+			meld_rates: meld_rates.unwrap_or_else(|| MeldRateTable::builtin().clone()),
+			default_level: default_level.unwrap_or_else(CraftingLevel::max),
+		}
+	}
+
+	/// Imports one teamcraft gearset or an array of them.
+	pub fn teamcraft(&self, json: &str) -> Result<ImportedGearsets, GearsetImportError> {
+		let gearsets = one_or_many(serde_json::from_str(json)?)
+			.iter()
+			.map(|gearset| {
+				let crystal = gearset["crystal"]["itemId"].as_u64().unwrap_or(0);
+				ExportedGearset {
+					job: gearset["job"].as_u64().unwrap_or(0) as u32,
+					level: level(&gearset["level"]),
+					items: TEAMCRAFT_SLOTS
+						.iter()
+						.filter_map(|slot| {
+							let piece = &gearset[*slot];
+							let item_id = id(&piece["itemId"])?;
+							let materia = piece["materias"]
+								.as_array()
+								.into_iter()
+								.flatten()
+								.filter_map(id)
+								.collect();
+							Some((item_id, hq(&piece["hq"]), materia))
+						})
+						.collect(),
+					specialist: crystal > 0,
+					food: consumable(&gearset["food"]),
+					medicine: consumable(&gearset["medicine"]),
+				}
+			})
+			.collect();
+		self.import(gearsets)
+	}
+
+	/// Imports one Etro gearset or an array of them. Etro doesn't say whether items and
+	/// food are HQ, so they are taken in HQ (see [`GearCatalog::item`]); neither does it
+	/// have soul crystals, so no gearset is a specialist one.
+	pub fn etro(&self, json: &str) -> Result<ImportedGearsets, GearsetImportError> {
+		let gearsets = one_or_many(serde_json::from_str(json)?)
+			.iter()
+			.map(|gearset| ExportedGearset {
+				job: gearset["job"].as_u64().unwrap_or(0) as u32,
+				level: level(&gearset["level"]),
+				items: ETRO_SLOTS
+					.iter()
+					.filter_map(|(slot, suffix)| {
+						let item_id = id(&gearset[*slot])?;
+						let melds = &gearset["materia"];
+						let melds = match &melds[format!("{}{}", item_id, suffix)] {
+							Value::Null => &melds[item_id.to_string()],
+							melds => melds,
+						};
+						let mut materia: Vec<(u32, u32)> = melds
+							.as_object()
+							.into_iter()
+							.flatten()
+							.filter_map(|(slot, materia)| Some((slot.parse().ok()?, id(materia)?)))
+							.collect();
+						materia.sort_unstable();
+						Some((
+							item_id,
+							true,
+							materia.into_iter().map(|(_, materia)| materia).collect(),
+						))
+					})
+					.collect(),
+				specialist: false,
+				food: id(&gearset["food"]).map(|food| (food, true)),
+				medicine: id(&gearset["medicine"]).map(|medicine| (medicine, true)),
+			})
+			.collect();
+		self.import(gearsets)
+	}
+
+	pub fn load_teamcraft(
+		&self,
+		path: impl AsRef<Path>,
+	) -> Result<ImportedGearsets, GearsetImportError> {
+		self.teamcraft(&fs::read_to_string(path)?)
+	}
+
+	pub fn load_etro(
+		&self,
+		path: impl AsRef<Path>,
+	) -> Result<ImportedGearsets, GearsetImportError> {
+		self.etro(&fs::read_to_string(path)?)
+	}
+
+	fn import(
+		&self,
+		gearsets: Vec<ExportedGearset>,
+	) -> Result<ImportedGearsets, GearsetImportError> {
+		let gearsets: Vec<_> = gearsets
+			.into_iter()
+			.filter_map(|gearset| {
+				let job = CraftingJob::from_class_job_id(gearset.job);
+				(job != CraftingJob::Any).then_some((job, gearset))
+			})
+			.collect();
+
+		let mut levels = CrafterLevels::default();
+		for (job, gearset) in &gearsets {
+			levels[*job] = match gearset.level {
+				Some(level) => {
+					CraftingLevel::new(level).ok_or(GearsetImportError::InvalidLevel(level))?
+				}
+				None => self.default_level,
+			};
+		}
+
+		let mut unknown_items = vec![];
+		let mut stats = HashMap::new();
+		for (job, gearset) in gearsets {
+			let mut items = vec![];
+			for (item_id, hq, materia_ids) in &gearset.items {
+				let Some(item) = self.catalog.item(*item_id, *hq) else {
+					unknown_items.push(*item_id);
+					continue;
+				};
+				let mut item = item.clone();
+				item.hq = *hq;
+				item.materia = vec![];
+				for materia_id in materia_ids {
+					match self.catalog.materia.get(materia_id) {
+						Some(materia) => item.materia.push(*materia),
+						None => unknown_items.push(*materia_id),
+					}
+				}
+				items.push(item);
+			}
+			let mut consumable = |consumable: Option<(u32, bool)>| {
+				let (item_id, hq) = consumable?;
+				let found = self.catalog.consumables.get(item_id);
+				if found.is_none() {
+					unknown_items.push(item_id);
+				}
+				found.map(|found| (found, hq))
+			};
+			let food = consumable(gearset.food);
+			let medicine = consumable(gearset.medicine);
+			if !unknown_items.is_empty() {
+				continue;
+			}
+
+			let crafter_stats = StatCalculator::builder()
+				.base_stats(self.base_stats.clone())
+				.meld_rates(self.meld_rates.clone())
+				.job(job)
+				.level(levels[job])
+				.levels(levels.clone())
+				.items(items)
+				.specialist(gearset.specialist)
+				.build()
+				.crafter_stats()?;
+			stats.insert(
+				job,
+				crafter_stats.with_consumables(
+					food.map(|(food, _)| food),
+					medicine.map(|(medicine, _)| medicine),
+					food.is_some_and(|(_, hq)| hq),
+					medicine.is_some_and(|(_, hq)| hq),
+				),
+			);
+		}
+
+		if !unknown_items.is_empty() {
+			unknown_items.sort_unstable();
+			unknown_items.dedup();
+			return Err(GearsetImportError::UnknownItems(unknown_items));
+		}
+		Ok(ImportedGearsets { stats, levels })
+	}
+}
+
+fn one_or_many(json: Value) -> Vec<Value> {
+	match json {
+		Value::Array(gearsets) => gearsets,
+		gearset => vec![gearset],
+	}
+}
+
+/// An item id, with 0 (and null) meaning an empty slot.
+fn id(value: &Value) -> Option<u32> {
+	value.as_u64().filter(|&id| id > 0).map(|id| id as u32)
+}
+
+fn hq(value: &Value) -> bool {
+	value.as_bool().unwrap_or(false)
+}
+
+fn level(value: &Value) -> Option<u8> {
+	value.as_u64().map(|level| level.min(u8::MAX as u64) as u8)
+}
+
+/// A consumable exported as its id or as an object with its id and quality.
+fn consumable(value: &Value) -> Option<(u32, bool)> {
+	match value {
+		Value::Object(_) => {
+			let item_id = ["itemId", "id", "ID"]
+				.iter()
+				.find_map(|key| id(&value[*key]))?;
+			Some((item_id, hq(&value["hq"]) || hq(&value["HQ"])))
+		}
+		value => id(value).map(|item_id| (item_id, false)),
+	}
+}
+
+#[derive(Debug)]
+pub enum GearsetImportError {
+	Io(std::io::Error),
+	Json(serde_json::Error),
+	/// Item ids, materia included, missing from the catalog.
+	UnknownItems(Vec<u32>),
+	InvalidLevel(u8),
+	Gear(GearError),
+}
+
+impl fmt::Display for GearsetImportError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			GearsetImportError::Io(err) => write!(f, "{}", err),
+			GearsetImportError::Json(err) => write!(f, "{}", err),
+			GearsetImportError::UnknownItems(item_ids) => {
+				let item_ids: Vec<_> = item_ids.iter().map(u32::to_string).collect();
+				write!(f, "unknown item ids: {}", item_ids.join(", "))
+			}
+			GearsetImportError::InvalidLevel(level) => write!(f, "invalid level {}", level),
+			GearsetImportError::Gear(err) => write!(f, "{}", err),
+		}
+	}
+}
+
+impl std::error::Error for GearsetImportError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			GearsetImportError::Io(err) => Some(err),
+			GearsetImportError::Json(err) => Some(err),
+			GearsetImportError::Gear(err) => Some(err),
+			_ => None,
+		}
+	}
+}
+
+impl From<std::io::Error> for GearsetImportError {
+	fn from(err: std::io::Error) -> Self {
+		GearsetImportError::Io(err)
+	}
+}
+
+impl From<serde_json::Error> for GearsetImportError {
+	fn from(err: serde_json::Error) -> Self {
+		GearsetImportError::Json(err)
+	}
+}
+
+impl From<GearError> for GearsetImportError {
+	fn from(err: GearError) -> Self {
+		GearsetImportError::Gear(err)
+	}
+}
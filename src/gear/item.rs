@@ -0,0 +1,270 @@
+use std::{
+	collections::BTreeMap,
+	fs::File,
+	io::Read,
+	ops::{Add, AddAssign},
+	path::Path,
+	sync::OnceLock,
+};
+
+use crate::{
+	recipes::{sheet::Sheet, RecipeDataError},
+	types::structs::CraftingLevel,
+};
+
+/// Materia can be melded into at most this many slots of an item.
+pub const MAX_MATERIA_SLOTS: usize = 5;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GearStat {
+	Craftsmanship,
+	Control,
+	Cp,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GearStats {
+	pub craftsmanship: u32,
+	pub control: u32,
+	pub cp: u32,
+}
+
+impl GearStats {
+	pub fn get(&self, stat: GearStat) -> u32 {
+		match stat {
+			GearStat::Craftsmanship => self.craftsmanship,
+			GearStat::Control => self.control,
+			GearStat::Cp => self.cp,
+		}
+	}
+
+	pub fn get_mut(&mut self, stat: GearStat) -> &mut u32 {
+		match stat {
+			GearStat::Craftsmanship => &mut self.craftsmanship,
+			GearStat::Control => &mut self.control,
+			GearStat::Cp => &mut self.cp,
+		}
+	}
+}
+
+impl Add for GearStats {
+	type Output = GearStats;
+
+	fn add(self, rhs: Self) -> Self::Output {
+		GearStats {
+			craftsmanship: self.craftsmanship + rhs.craftsmanship,
+			control: self.control + rhs.control,
+			cp: self.cp + rhs.cp,
+		}
+	}
+}
+
+impl AddAssign for GearStats {
+	fn add_assign(&mut self, rhs: Self) {
+		*self = *self + rhs;
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Materia {
+	pub stat: GearStat,
+	pub value: u32,
+	/// Grade of the materia, from 1 for Materia I.
+	pub grade: u32,
+}
+
+/// A piece of equipment and the materia melded into it, in slot order.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GearItem {
+	pub id: u32,
+	pub name: String,
+	pub hq: bool,
+	pub stats: GearStats,
+	/// The most each stat of the item can reach through melding; uncapped if `None`.
+	pub stat_caps: Option<GearStats>,
+	/// Slots that always accept materia.
+	pub materia_slots: u32,
+	/// Whether materia can be overmelded past `materia_slots`.
+	pub advanced_melding: bool,
+	pub materia: Vec<Materia>,
+}
+
+impl GearItem {
+	/// What the materia add to the item, each stat limited by the item's cap.
+	pub fn materia_stats(&self) -> GearStats {
+		let mut added = GearStats::default();
+		for materia in &self.materia {
+			*added.get_mut(materia.stat) += materia.value;
+		}
+		if let Some(caps) = &self.stat_caps {
+			for stat in [GearStat::Craftsmanship, GearStat::Control, GearStat::Cp] {
+				let room = caps.get(stat).saturating_sub(self.stats.get(stat));
+				let value = added.get_mut(stat);
+				*value = (*value).min(room);
+			}
+		}
+		added
+	}
+
+	pub fn total_stats(&self) -> GearStats {
+		self.stats + self.materia_stats()
+	}
+
+	/// Chance in percent of melding the materia of each slot, or `None` for a slot the
+	/// materia can't be melded into at all, including overmelds of grades missing from
+	/// `rates`.
+	pub fn meld_success_rates(&self, rates: &MeldRateTable) -> Vec<Option<u32>> {
+		self.materia
+			.iter()
+			.enumerate()
+			.map(|(slot, materia)| {
+				if slot < self.materia_slots as usize {
+					Some(100)
+				} else if !self.advanced_melding || slot >= MAX_MATERIA_SLOTS {
+					None
+				} else {
+					rates
+						.rate(materia.grade, slot - self.materia_slots as usize, self.hq)
+						.filter(|&rate| rate > 0)
+				}
+			})
+			.collect()
+	}
+
+	/// The first slot whose materia can't be there, i.e. whose
+	/// [success rate](Self::meld_success_rates) is `None`.
+	pub fn invalid_meld(&self, rates: &MeldRateTable) -> Option<usize> {
+		self.meld_success_rates(rates)
+			.iter()
+			.position(Option::is_none)
+	}
+}
+
+/// Stats a crafter has with nothing equipped, by level.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BaseStatsTable {
+	rows: BTreeMap<u8, GearStats>,
+}
+
+impl BaseStatsTable {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The game's base stats of every crafting job: 180 CP and no craftsmanship or
+	/// control at any level, those coming from gear only.
+	pub fn builtin() -> &'static BaseStatsTable {
+		static BUILTIN: OnceLock<BaseStatsTable> = OnceLock::new();
+		BUILTIN.get_or_init(|| {
+			(1..=u8::from(CraftingLevel::max()))
+				.map(|level| {
+					let stats = GearStats {
+						craftsmanship: 0,
+						control: 0,
+						cp: 180,
+					};
+					(level, stats)
+				})
+				.collect()
+		})
+	}
+
+	pub fn insert(&mut self, level: u8, stats: GearStats) {
+		self.rows.insert(level, stats);
+	}
+
+	pub fn get(&self, level: u8) -> Option<GearStats> {
+		self.rows.get(&level).copied()
+	}
+}
+
+impl FromIterator<(u8, GearStats)> for BaseStatsTable {
+	fn from_iter<T: IntoIterator<Item = (u8, GearStats)>>(iter: T) -> Self {
+		Self {
+			rows: iter.into_iter().collect(),
+		}
+	}
+}
+
+/// Overmeld success rates by materia grade, as in the MateriaJoinRate sheet.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MeldRateTable {
+	/// (NQ item rates, HQ item rates), for the first to the last overmeld slot.
+	rows: BTreeMap<u32, ([u32; 4], [u32; 4])>,
+}
+
+impl MeldRateTable {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The MateriaJoinRate rows of Materia I to XII.
+	pub fn builtin() -> &'static MeldRateTable {
+		static BUILTIN: OnceLock<MeldRateTable> = OnceLock::new();
+		BUILTIN.get_or_init(|| {
+			let mut table = Self::new();
+			let rows = [
+				([90, 48, 28, 16], [80, 40, 20, 10]),
+				([82, 44, 26, 16], [72, 36, 18, 9]),
+				([70, 38, 22, 14], [60, 30, 15, 8]),
+				([58, 32, 20, 12], [48, 24, 12, 6]),
+				// odd grades from V on can be overmelded anywhere, even grades only once
+				([17, 10, 7, 5], [12, 6, 3, 2]),
+				([17, 0, 0, 0], [12, 0, 0, 0]),
+				([17, 10, 7, 5], [12, 6, 3, 2]),
+				([17, 0, 0, 0], [12, 0, 0, 0]),
+				([17, 10, 7, 5], [12, 6, 3, 2]),
+				([17, 0, 0, 0], [12, 0, 0, 0]),
+				([17, 10, 7, 5], [12, 6, 3, 2]),
+				([17, 0, 0, 0], [12, 0, 0, 0]),
+			];
+			for (grade, (nq, hq)) in (1..).zip(rows) {
+				table.insert(grade, nq, hq);
+			}
+			table
+		})
+	}
+
+	/// Loads `MateriaJoinRate.csv`, whose rows are keyed by grade from 0 for Materia I.
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, RecipeDataError> {
+		Self::from_reader(File::open(path)?)
+	}
+
+	pub fn from_reader(materia_join_rate: impl Read) -> Result<Self, RecipeDataError> {
+		let sheet = Sheet::read("MateriaJoinRate", materia_join_rate)?;
+		let mut table = Self::new();
+		for row in sheet.rows() {
+			let grade = row.u32(&["#", "key", "row_id"])? + 1;
+			let mut nq = [0; 4];
+			let mut hq = [0; 4];
+			for ix in 0..4 {
+				nq[ix] = row.u32(&[
+					&format!("NQOvermeldPercent[{}]", ix),
+					&format!("NqOvermeldPercent[{}]", ix),
+				])?;
+				hq[ix] = row.u32(&[
+					&format!("HQOvermeldPercent[{}]", ix),
+					&format!("HqOvermeldPercent[{}]", ix),
+				])?;
+			}
+			table.insert(grade, nq, hq);
+		}
+		Ok(table)
+	}
+
+	pub fn insert(&mut self, grade: u32, nq: [u32; 4], hq: [u32; 4]) {
+		self.rows.insert(grade, (nq, hq));
+	}
+
+	/// The chance in percent of melding a materia into the `overmeld`-th overmeld slot
+	/// (from 0) of an NQ or HQ item, if known.
+	pub fn rate(&self, grade: u32, overmeld: usize, hq: bool) -> Option<u32> {
+		let (nq_rates, hq_rates) = self.rows.get(&grade)?;
+		let rates = if hq { hq_rates } else { nq_rates };
+		rates.get(overmeld).copied()
+	}
+}
@@ -0,0 +1,14 @@
+mod calculator;
+mod catalog;
+mod consumables;
+#[cfg(feature = "serde")]
+mod import;
+mod item;
+mod optimizer;
+pub use calculator::*;
+pub use catalog::*;
+pub use consumables::*;
+#[cfg(feature = "serde")]
+pub use import::*;
+pub use item::*;
+pub use optimizer::*;
@@ -0,0 +1,257 @@
+use bon::bon;
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::types::{
+	enums::CraftingActionEnum,
+	structs::{Craft, CrafterStats},
+	Simulation,
+};
+
+use super::{GearItem, Materia, StatCalculator, MAX_MATERIA_SLOTS};
+
+/// A materia that can be melded and what one costs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MateriaOption {
+	pub materia: Materia,
+	pub cost: u64,
+}
+
+/// Picks the materia to meld into a gearset's open slots so that a rotation reaches a
+/// quality target for the lowest expected materia cost.
+///
+/// Candidates are tried from the cheapest up, each simulated linearly with the stats
+/// the melds give (within every item's caps), so the first one reaching the target is
+/// the cheapest. Failed overmelds cost materia too: a meld's expected cost is the
+/// materia's cost divided by its success rate, and the melds of an item are put in
+/// the slot order making them the cheapest.
+pub struct MeldOptimizer {
+	calculator: StatCalculator,
+	recipe: Craft,
+	actions: Vec<CraftingActionEnum>,
+	materia: Vec<MateriaOption>,
+	target_quality: u32,
+	max_candidates: usize,
+}
+
+#[bon]
+impl MeldOptimizer {
+	/// * `calculator`: the gearset, whose items' materia are kept as they are
+	/// * `materia`: what can be melded into the open slots
+	/// * `target_quality`: the quality to reach, the recipe's maximum quality (i.e. 100% HQ)
+	///   by default
+	/// * `max_candidates`: how many meld combinations to simulate before giving up,
+	///   100 000 by default
+	#[builder]
+	pub fn new(
+		calculator: StatCalculator,
+		recipe: Craft,
+		actions: Vec<CraftingActionEnum>,
+		materia: Vec<MateriaOption>,
+		target_quality: Option<u32>,
+		max_candidates: Option<usize>,
+	) -> Self {
+		Self {
+			target_quality: target_quality.unwrap_or(recipe.quality),
+			max_candidates: max_candidates.unwrap_or(100_000),
+			calculator,
+			recipe,
+			actions,
+			materia,
+		}
+	}
+
+	/// Returns the cheapest melds reaching the target, or `None` if no combination within
+	/// `max_candidates` does (or the gearset itself is invalid).
+	pub fn solve(&self) -> Option<MeldPlan> {
+		let items = &self.calculator.items;
+		let open_slots: Vec<usize> = items
+			.iter()
+			.map(|item| {
+				let slots = if item.advanced_melding {
+					MAX_MATERIA_SLOTS
+				} else {
+					item.materia_slots as usize
+				};
+				slots.saturating_sub(item.materia.len())
+			})
+			.collect();
+
+		// (expected cost in hundredths, insertion order, melds as (item, materia option)
+		// pairs in order, so that every combination is only reached once)
+		let mut queue = BinaryHeap::new();
+		queue.push(Reverse((0, 0, vec![])));
+		let mut pushed = 1;
+		let mut tried = 0;
+
+		while let Some(Reverse((cost, _, melds))) = queue.pop() {
+			tried += 1;
+			if tried > self.max_candidates {
+				return None;
+			}
+
+			let (gear, planned) = self.meld(&melds)?;
+			let mut calculator = self.calculator.clone();
+			calculator.items = gear;
+			let crafter_stats = calculator.crafter_stats().ok()?;
+			let result = Simulation::builder()
+				.recipe(self.recipe.clone())
+				.crafter_stats(crafter_stats.clone())
+				.actions(self.actions.clone())
+				.build()
+				.start()
+				.linear(true)
+				.run();
+			if result.success && result.simulation.quality >= self.target_quality {
+				return Some(MeldPlan {
+					items: calculator.items,
+					melds: planned,
+					expected_cost: cost as f64 / 100.0,
+					crafter_stats,
+					quality: result.simulation.quality,
+					hq_percent: result.hq_percent,
+				});
+			}
+
+			let (first_item, first_option) = melds.last().copied().unwrap_or((0, 0));
+			for (item_ix, item) in items.iter().enumerate().skip(first_item) {
+				let used = melds.iter().filter(|&&(ix, _)| ix == item_ix).count();
+				if used >= open_slots[item_ix] {
+					continue;
+				}
+				let first_option = if item_ix == first_item {
+					first_option
+				} else {
+					0
+				};
+				for option_ix in first_option..self.materia.len() {
+					// materia past the item's cap only add cost
+					let mut with_materia = item.clone();
+					with_materia.materia.extend(
+						melds
+							.iter()
+							.filter(|&&(ix, _)| ix == item_ix)
+							.chain([(item_ix, option_ix)].iter())
+							.map(|&(_, option)| self.materia[option].materia),
+					);
+					let mut without_materia = with_materia.clone();
+					without_materia.materia.pop();
+					if with_materia.materia_stats() == without_materia.materia_stats() {
+						continue;
+					}
+
+					let mut next = melds.clone();
+					next.push((item_ix, option_ix));
+					if let Some(next_cost) = self.meld_cost(&next) {
+						queue.push(Reverse((next_cost, pushed, next)));
+						pushed += 1;
+					}
+				}
+			}
+		}
+		None
+	}
+
+	/// Expected cost of the melds in hundredths, `None` if they can't all be melded.
+	fn meld_cost(&self, melds: &[(usize, usize)]) -> Option<u64> {
+		self.meld(melds).map(|(_, planned)| {
+			planned
+				.iter()
+				.map(|meld| (meld.expected_cost * 100.0).round() as u64)
+				.sum()
+		})
+	}
+
+	/// The gear with the melds done, each item's melds in their cheapest slot order.
+	fn meld(&self, melds: &[(usize, usize)]) -> Option<(Vec<GearItem>, Vec<PlannedMeld>)> {
+		let mut gear = self.calculator.items.clone();
+		let mut planned = vec![];
+		for (item_ix, item) in gear.iter_mut().enumerate() {
+			let options: Vec<_> = melds
+				.iter()
+				.filter(|&&(ix, _)| ix == item_ix)
+				.map(|&(_, option)| self.materia[option])
+				.collect();
+			if options.is_empty() {
+				continue;
+			}
+
+			let first_slot = item.materia.len();
+			let mut best: Option<(f64, Vec<PlannedMeld>, GearItem)> = None;
+			for order in permutations(options.len()) {
+				let mut melded = item.clone();
+				melded
+					.materia
+					.extend(order.iter().map(|&ix| options[ix].materia));
+				let rates = melded.meld_success_rates(&self.calculator.meld_rates);
+				let Some(item_melds) = order
+					.iter()
+					.enumerate()
+					.map(|(ix, &option)| {
+						let success_rate = rates[first_slot + ix]?;
+						Some(PlannedMeld {
+							item_id: item.id,
+							slot: first_slot + ix,
+							materia: options[option].materia,
+							success_rate,
+							expected_cost: options[option].cost as f64 * 100.0
+								/ success_rate as f64,
+						})
+					})
+					.collect::<Option<Vec<_>>>()
+				else {
+					continue;
+				};
+				let cost: f64 = item_melds.iter().map(|meld| meld.expected_cost).sum();
+				if best
+					.as_ref()
+					.map_or(true, |(best_cost, _, _)| cost < *best_cost)
+				{
+					best = Some((cost, item_melds, melded));
+				}
+			}
+
+			let (_, item_melds, melded) = best?;
+			planned.extend(item_melds);
+			*item = melded;
+		}
+		Some((gear, planned))
+	}
+}
+
+/// Every order of `0..len`.
+fn permutations(len: usize) -> Vec<Vec<usize>> {
+	if len == 0 {
+		return vec![vec![]];
+	}
+	let mut orders = vec![];
+	for order in permutations(len - 1) {
+		for ix in 0..=order.len() {
+			let mut next = order.clone();
+			next.insert(ix, len - 1);
+			orders.push(next);
+		}
+	}
+	orders
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlannedMeld {
+	pub item_id: u32,
+	pub slot: usize,
+	pub materia: Materia,
+	pub success_rate: u32,
+	/// The materia's cost over the success rate.
+	pub expected_cost: f64,
+}
+
+#[derive(Clone)]
+pub struct MeldPlan {
+	/// The gearset with the melds done.
+	pub items: Vec<GearItem>,
+	pub melds: Vec<PlannedMeld>,
+	pub expected_cost: f64,
+	pub crafter_stats: CrafterStats,
+	/// Quality at the end of the rotation.
+	pub quality: u32,
+	pub hq_percent: u32,
+}
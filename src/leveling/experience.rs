@@ -0,0 +1,97 @@
+use std::{collections::BTreeMap, fs::File, io::Read, path::Path, sync::OnceLock};
+
+use crate::{
+	recipes::{sheet::Sheet, RecipeDataError},
+	types::structs::CraftingLevel,
+};
+
+/// The EXP needed to go from each level to the next, as in the ParamGrow sheet.
+///
+/// Every job, crafting ones included, levels with the same table.
+/// [`ExperienceTable::builtin`] has every level up to the level cap; a newer ParamGrow
+/// sheet can be [loaded](ExperienceTable::load) instead.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExperienceTable {
+	exp_to_next: BTreeMap<u8, u32>,
+}
+
+impl ExperienceTable {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn builtin() -> &'static ExperienceTable {
+		static BUILTIN: OnceLock<ExperienceTable> = OnceLock::new();
+		BUILTIN.get_or_init(|| {
+			EXP_TO_NEXT
+				.into_iter()
+				.zip(1..)
+				.map(|(exp_to_next, level)| (CraftingLevel::unchecked_new(level), exp_to_next))
+				.collect()
+		})
+	}
+
+	/// Loads `ParamGrow.csv`, with its `ExpToNext` column keyed by level.
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, RecipeDataError> {
+		Self::from_reader(File::open(path)?)
+	}
+
+	pub fn from_reader(param_grow: impl Read) -> Result<Self, RecipeDataError> {
+		let sheet = Sheet::read("ParamGrow", param_grow)?;
+		let mut table = Self::new();
+		for row in sheet.rows() {
+			let level = row.u32(&["#", "key", "row_id"])?;
+			let exp_to_next = row.u32(&["ExpToNext"])?;
+			// level 0 and the levels past the cap have nothing to gain
+			if let (Ok(level), true) = (u8::try_from(level), exp_to_next > 0) {
+				if let Some(level) = CraftingLevel::new(level) {
+					table.insert(level, exp_to_next);
+				}
+			}
+		}
+		Ok(table)
+	}
+
+	pub fn insert(&mut self, level: CraftingLevel, exp_to_next: u32) {
+		self.exp_to_next.insert(level.into(), exp_to_next);
+	}
+
+	/// The EXP to gain at `level` to reach the next one, if known.
+	pub fn exp_to_next(&self, level: CraftingLevel) -> Option<u32> {
+		self.exp_to_next.get(&u8::from(level)).copied()
+	}
+
+	/// The EXP needed from the start of level `from` to reach level `to`.
+	pub fn total_exp(&self, from: CraftingLevel, to: CraftingLevel) -> Option<u64> {
+		(u8::from(from)..u8::from(to))
+			.map(|level| {
+				self.exp_to_next(CraftingLevel::unchecked_new(level))
+					.map(u64::from)
+			})
+			.sum()
+	}
+}
+
+/// The EXP to next level of levels 1 to 99, level 100 being the cap.
+const EXP_TO_NEXT: [u32; 99] = [
+	300, 600, 1100, 1700, 2300, 4200, 6000, 7350, 9930, 11800, 15600, 19600, 23700, 26400, 30500,
+	35400, 40500, 45700, 51000, 56600, 63900, 71400, 79100, 87100, 95200, 109800, 124800, 140200,
+	155900, 162500, 175900, 189600, 203500, 217900, 232320, 249900, 267800, 286200, 304900, 324000,
+	340200, 356800, 373700, 390800, 408200, 437600, 467500, 498000, 529000, 864000, 1058400,
+	1267200, 1555200, 1872000, 2217600, 2592000, 2995200, 3427200, 3888000, 4470000, 4873000,
+	5316000, 5809000, 6364000, 6995000, 7722000, 8575000, 9593000, 10826000, 12449000, 13881000,
+	15338000, 16818000, 18322000, 19850000, 21402000, 22978000, 24578000, 26202000, 29480000,
+	30940000, 32420000, 33920000, 35440000, 36980000, 38540000, 40120000, 41720000, 43340000,
+	47970000, 49970000, 51990000, 54030000, 56090000, 58170000, 60270000, 62390000, 64530000,
+	66690000,
+];
+
+impl FromIterator<(CraftingLevel, u32)> for ExperienceTable {
+	fn from_iter<T: IntoIterator<Item = (CraftingLevel, u32)>>(iter: T) -> Self {
+		let mut table = Self::new();
+		for (level, exp_to_next) in iter {
+			table.insert(level, exp_to_next);
+		}
+		table
+	}
+}
@@ -0,0 +1,4 @@
+mod experience;
+mod planner;
+pub use experience::*;
+pub use planner::*;
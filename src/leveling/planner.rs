@@ -0,0 +1,200 @@
+use bon::bon;
+
+use crate::types::{
+	enums::{CraftingActionEnum, CraftingJob},
+	structs::{Craft, CrafterStats, CraftingLevel},
+	Simulation,
+};
+
+use super::ExperienceTable;
+
+/// A recipe to level with, the EXP it gives and the rotations that can be used on it.
+#[derive(Clone)]
+pub struct LevelingRecipe {
+	pub recipe: Craft,
+	/// EXP of a normal quality craft.
+	pub base_exp: u32,
+	pub rotations: Vec<Vec<CraftingActionEnum>>,
+}
+
+/// Plans the crafts taking a job from its current level to a target level.
+///
+/// At every level the recipe and rotation expected to give the most EXP per craft are
+/// used, which is also what reaches the target in the fewest crafts: the recipes
+/// available only get better with levels. Rotations are simulated linearly, each
+/// HQ chance giving its share of the HQ bonus EXP.
+pub struct LevelingPlanner {
+	experience_table: ExperienceTable,
+	crafter_stats: CrafterStats,
+	job: CraftingJob,
+	recipes: Vec<LevelingRecipe>,
+	level: CraftingLevel,
+	exp: u32,
+	target_level: CraftingLevel,
+	hq_bonus: u32,
+}
+
+#[bon]
+impl LevelingPlanner {
+	/// * `experience_table`: the [built-in](ExperienceTable::builtin) one by default
+	/// * `level`, `exp`: where the job is now, the crafter stats' level for `job` and no
+	///   EXP by default
+	/// * `hq_bonus`: extra EXP of an HQ craft, in percent of the base EXP, 100 (double
+	///   EXP) by default
+	#[builder]
+	pub fn new(
+		experience_table: Option<ExperienceTable>,
+		crafter_stats: CrafterStats,
+		job: CraftingJob,
+		recipes: Vec<LevelingRecipe>,
+		level: Option<CraftingLevel>,
+		exp: Option<u32>,
+		target_level: CraftingLevel,
+		hq_bonus: Option<u32>,
+	) -> Self {
+		Self {
+			level: level.unwrap_or_else(|| crafter_stats.level_for(job)),
+			exp: exp.unwrap_or(0),
+			hq_bonus: hq_bonus.unwrap_or(100),
+			experience_table: experience_table
+				.unwrap_or_else(|| ExperienceTable::builtin().clone()),
+			crafter_stats,
+			job,
+			recipes,
+			target_level,
+		}
+	}
+
+	/// Returns the plan, or `None` if a level isn't in the experience table or has no
+	/// recipe that can be crafted successfully.
+	pub fn plan(&self) -> Option<LevelingPlan> {
+		let mut level = self.level;
+		let mut exp = self.exp;
+		let mut brackets: Vec<LevelingBracket> = vec![];
+
+		while level < self.target_level {
+			let exp_to_next = self.experience_table.exp_to_next(level)?;
+			let (recipe_ix, rotation, hq_percent, exp_per_craft) = self.best_craft(level)?;
+
+			let count = (exp_to_next.saturating_sub(exp) + exp_per_craft - 1) / exp_per_craft;
+			let start_level = level;
+			exp += count * exp_per_craft;
+			while level < self.target_level {
+				match self.experience_table.exp_to_next(level) {
+					Some(exp_to_next) if exp >= exp_to_next => {
+						exp -= exp_to_next;
+						level = CraftingLevel::unchecked_new(u8::from(level) + 1);
+					}
+					_ => break,
+				}
+			}
+
+			let recipe = &self.recipes[recipe_ix].recipe;
+			match brackets.last_mut() {
+				// the starting EXP was already enough for a level
+				_ if count == 0 => {}
+				Some(last) if last.recipe.id == recipe.id && last.rotation == rotation => {
+					last.to_level = level;
+					last.count += count;
+				}
+				_ => brackets.push(LevelingBracket {
+					from_level: start_level,
+					to_level: level,
+					recipe: recipe.clone(),
+					count,
+					rotation,
+					hq_percent,
+					exp_per_craft,
+				}),
+			}
+		}
+
+		Some(LevelingPlan {
+			total_crafts: brackets.iter().map(|bracket| bracket.count).sum(),
+			brackets,
+			level,
+			exp,
+		})
+	}
+
+	/// The recipe and rotation giving the most expected EXP at a level, as
+	/// (recipe index, rotation, HQ percent, EXP per craft).
+	fn best_craft(
+		&self,
+		level: CraftingLevel,
+	) -> Option<(usize, Vec<CraftingActionEnum>, u32, u32)> {
+		let mut crafter_stats = self.crafter_stats.clone();
+		crafter_stats.level = level;
+		if self.job != CraftingJob::Any {
+			crafter_stats.levels[self.job] = level;
+		}
+
+		let mut best: Option<(usize, Vec<CraftingActionEnum>, u32, u32)> = None;
+		for (ix, leveling_recipe) in self.recipes.iter().enumerate() {
+			let recipe = &leveling_recipe.recipe;
+			if recipe.lvl > level
+				|| crafter_stats.craftsmanship < recipe.craftsmanship_req.unwrap_or(0)
+				|| crafter_stats.control < recipe.control_req.unwrap_or(0)
+			{
+				continue;
+			}
+
+			for rotation in &leveling_recipe.rotations {
+				let result = Simulation::builder()
+					.recipe(recipe.clone())
+					.crafter_stats(crafter_stats.clone())
+					.actions(rotation.clone())
+					.build()
+					.start()
+					.linear(true)
+					.run();
+				if !result.success {
+					continue;
+				}
+
+				let hq_percent = if recipe.hq == Some(false) {
+					0
+				} else {
+					result.hq_percent
+				};
+				let bonus =
+					leveling_recipe.base_exp as u64 * self.hq_bonus as u64 * hq_percent as u64
+						/ 10_000;
+				let exp_per_craft = (leveling_recipe.base_exp + bonus as u32).max(1);
+				let better = best
+					.as_ref()
+					.map_or(true, |(_, best_rotation, _, best_exp)| {
+						(exp_per_craft, std::cmp::Reverse(rotation.len()))
+							> (*best_exp, std::cmp::Reverse(best_rotation.len()))
+					});
+				if better {
+					best = Some((ix, rotation.clone(), hq_percent, exp_per_craft));
+				}
+			}
+		}
+		best
+	}
+}
+
+/// A range of levels crafting the same recipe with the same rotation.
+#[derive(Clone)]
+pub struct LevelingBracket {
+	pub from_level: CraftingLevel,
+	/// The level reached after the crafts.
+	pub to_level: CraftingLevel,
+	pub recipe: Craft,
+	pub count: u32,
+	pub rotation: Vec<CraftingActionEnum>,
+	pub hq_percent: u32,
+	/// Expected EXP of one craft, counting the chance of HQ bonus EXP.
+	pub exp_per_craft: u32,
+}
+
+#[derive(Clone)]
+pub struct LevelingPlan {
+	pub brackets: Vec<LevelingBracket>,
+	pub total_crafts: u32,
+	/// The level and EXP at the end of the plan.
+	pub level: CraftingLevel,
+	pub exp: u32,
+}
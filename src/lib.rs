@@ -37,7 +37,20 @@
 //! ```
 
 #![forbid(unsafe_code)]
+pub mod evaluation;
+pub mod game_macro;
+pub mod gear;
+pub mod leveling;
+#[cfg(feature = "market")]
+pub mod market;
+pub mod recipes;
+pub mod solver;
+#[cfg(feature = "serde")]
+pub mod teamcraft;
 pub mod types;
+pub mod workshop;
+#[cfg(feature = "xivapi")]
+pub mod xivapi;
 
 #[cfg(test)]
 mod tests;
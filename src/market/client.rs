@@ -0,0 +1,179 @@
+use bon::bon;
+use serde_json::Value;
+use std::{collections::HashMap, fmt};
+
+use crate::{
+	recipes::{CraftingList, RecipeLookup},
+	types::{structs::Ingredient, Simulation},
+};
+
+use super::{CraftEstimate, ItemPrices};
+
+pub const DEFAULT_BASE_URL: &str = "https://universalis.app/api/v2";
+
+/// The most items Universalis accepts in one request.
+const MAX_ITEMS_PER_REQUEST: usize = 100;
+
+/// Fetches market board prices from a Universalis-compatible server.
+pub struct MarketClient {
+	http: reqwest::Client,
+	base_url: String,
+	world: String,
+}
+
+#[bon]
+impl MarketClient {
+	/// `world` is the world, data center or region to get prices for;
+	/// `base_url` defaults to [`DEFAULT_BASE_URL`].
+	#[builder]
+	pub fn new(world: String, base_url: Option<String>) -> Self {
+		Self {
+			http: reqwest::Client::new(),
+			base_url: base_url
+				.unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+				.trim_end_matches('/')
+				.to_string(),
+			world,
+		}
+	}
+
+	/// The cheapest NQ and HQ listings of each item. Items the server doesn't know are
+	/// left out.
+	pub async fn prices(&self, item_ids: &[u32]) -> Result<HashMap<u32, ItemPrices>, MarketError> {
+		let mut item_ids = item_ids.to_vec();
+		item_ids.sort_unstable();
+		item_ids.dedup();
+
+		let mut prices = HashMap::new();
+		for chunk in item_ids.chunks(MAX_ITEMS_PER_REQUEST) {
+			let ids: Vec<_> = chunk.iter().map(u32::to_string).collect();
+			let body = self
+				.http
+				.get(format!("{}/{}/{}", self.base_url, self.world, ids.join(",")))
+				.send()
+				.await?
+				.error_for_status()?
+				.bytes()
+				.await?;
+			let response: Value = serde_json::from_slice(&body)?;
+
+			// a single item is returned on its own rather than in an `items` map
+			let items: Vec<&Value> = match response.get("items").and_then(Value::as_object) {
+				Some(items) => items.values().collect(),
+				None => vec![&response],
+			};
+			for item in items {
+				let Some(item_id) = item["itemID"].as_u64() else {
+					continue;
+				};
+				let price = |field: &str| {
+					item[field]
+						.as_f64()
+						.filter(|&price| price > 0.0)
+						.map(|price| price as u32)
+				};
+				prices.insert(
+					item_id as u32,
+					ItemPrices {
+						nq: price("minPriceNQ"),
+						hq: price("minPriceHQ"),
+					},
+				);
+			}
+		}
+		Ok(prices)
+	}
+
+	/// Fetches the prices of the result and its materials, then estimates the craft's
+	/// profit. See [`CraftEstimate::new`] for the parameters.
+	pub async fn estimate(
+		&self,
+		simulation: &Simulation,
+		result_item_id: u32,
+		materials: Option<&[Ingredient]>,
+		hq_ingredients: Option<&[Ingredient]>,
+	) -> Result<CraftEstimate, MarketError> {
+		let mut item_ids: Vec<u32> = materials
+			.unwrap_or(&simulation.recipe.ingredients)
+			.iter()
+			.filter_map(|ingredient| ingredient.id.parse().ok())
+			.collect();
+		item_ids.push(result_item_id);
+
+		let prices = self.prices(&item_ids).await?;
+		Ok(CraftEstimate::new(
+			simulation,
+			materials,
+			hq_ingredients,
+			prices.get(&result_item_id).copied().unwrap_or_default(),
+			&prices,
+		))
+	}
+
+	/// Like [`estimate`](Self::estimate), but buys the raw materials of the recipe's
+	/// whole ingredient tree, crafting every ingredient `lookup` has a recipe for.
+	///
+	/// The intermediate crafts are listed in the estimate; `hq_ingredients` are then
+	/// raw materials.
+	pub async fn estimate_tree(
+		&self,
+		simulation: &Simulation,
+		result_item_id: u32,
+		lookup: &impl RecipeLookup,
+		hq_ingredients: Option<&[Ingredient]>,
+	) -> Result<CraftEstimate, MarketError> {
+		let targets: Vec<_> = simulation
+			.recipe
+			.ingredients
+			.iter()
+			.map(|ingredient| (ingredient.id.as_str(), ingredient.amount))
+			.collect();
+		let list = CraftingList::new(lookup, &targets);
+		let mut estimate = self
+			.estimate(
+				simulation,
+				result_item_id,
+				Some(&list.raw_materials),
+				hq_ingredients,
+			)
+			.await?;
+		estimate.intermediate_crafts = list.crafts;
+		Ok(estimate)
+	}
+}
+
+#[derive(Debug)]
+pub enum MarketError {
+	Http(reqwest::Error),
+	Json(serde_json::Error),
+}
+
+impl fmt::Display for MarketError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			MarketError::Http(err) => write!(f, "{}", err),
+			MarketError::Json(err) => write!(f, "{}", err),
+		}
+	}
+}
+
+impl std::error::Error for MarketError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			MarketError::Http(err) => Some(err),
+			MarketError::Json(err) => Some(err),
+		}
+	}
+}
+
+impl From<reqwest::Error> for MarketError {
+	fn from(err: reqwest::Error) -> Self {
+		MarketError::Http(err)
+	}
+}
+
+impl From<serde_json::Error> for MarketError {
+	fn from(err: serde_json::Error) -> Self {
+		MarketError::Json(err)
+	}
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use crate::{
+	recipes::CraftingListEntry,
+	types::{structs::Ingredient, Simulation},
+};
+
+/// The cheapest current listings of an item, `None` when nothing is listed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ItemPrices {
+	pub nq: Option<u32>,
+	pub hq: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MaterialCost {
+	pub item_id: u32,
+	pub amount: u32,
+	pub hq: bool,
+	pub unit_price: Option<u32>,
+}
+
+impl MaterialCost {
+	pub fn total(&self) -> Option<u64> {
+		self.unit_price
+			.map(|unit_price| unit_price as u64 * self.amount as u64)
+	}
+}
+
+/// What a craft costs and earns at current market prices.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CraftEstimate {
+	pub materials: Vec<MaterialCost>,
+	/// Cost of every material with a price; see `missing_prices` for the others.
+	pub material_cost: u64,
+	/// Materials without any listing of the needed quality.
+	pub missing_prices: Vec<u32>,
+	/// The intermediate crafts made from the materials, when the recipe's ingredients
+	/// were expanded into their ingredient tree.
+	pub intermediate_crafts: Vec<CraftingListEntry>,
+	pub hq_percent: u32,
+	/// Expected value of everything one craft yields, weighing the HQ and NQ prices
+	/// by the chance of an HQ result.
+	pub sale_value: f64,
+	pub profit: f64,
+}
+
+impl CraftEstimate {
+	/// Prices a finished (or planned) craft.
+	///
+	/// * `simulation`: the craft, whose quality gives the HQ chance
+	/// * `materials`: what has to be bought, the recipe's ingredients unless given, e.g.
+	///   when buying the raw materials of intermediate crafts instead
+	/// * `hq_ingredients`: how many of each material are bought HQ
+	pub fn new(
+		simulation: &Simulation,
+		materials: Option<&[Ingredient]>,
+		hq_ingredients: Option<&[Ingredient]>,
+		result_prices: ItemPrices,
+		material_prices: &HashMap<u32, ItemPrices>,
+	) -> Self {
+		let recipe = &simulation.recipe;
+		let materials = materials.unwrap_or(&recipe.ingredients);
+		let hq_amount = |item_id: &str| {
+			hq_ingredients
+				.into_iter()
+				.flatten()
+				.filter(|ingredient| ingredient.id == item_id)
+				.map(|ingredient| ingredient.amount)
+				.sum::<u32>()
+		};
+
+		let mut costs = vec![];
+		for ingredient in materials {
+			let Ok(item_id) = ingredient.id.parse::<u32>() else {
+				continue;
+			};
+			let prices = material_prices.get(&item_id).copied().unwrap_or_default();
+			let hq = hq_amount(&ingredient.id).min(ingredient.amount);
+			for (amount, is_hq, unit_price) in [
+				(ingredient.amount - hq, false, prices.nq),
+				(hq, true, prices.hq),
+			] {
+				if amount > 0 {
+					costs.push(MaterialCost {
+						item_id,
+						amount,
+						hq: is_hq,
+						unit_price,
+					});
+				}
+			}
+		}
+
+		let material_cost = costs.iter().filter_map(MaterialCost::total).sum();
+		let mut missing_prices: Vec<_> = costs
+			.iter()
+			.filter(|cost| cost.unit_price.is_none())
+			.map(|cost| cost.item_id)
+			.collect();
+		missing_prices.dedup();
+
+		let hq_percent = if recipe.hq == Some(false) {
+			0
+		} else {
+			simulation.get_hq_percent()
+		};
+		let hq_chance = hq_percent as f64 / 100.0;
+		// with only one quality listed, the other is assumed to sell for the same
+		let nq_price = result_prices.nq.or(result_prices.hq).unwrap_or(0) as f64;
+		let hq_price = result_prices.hq.or(result_prices.nq).unwrap_or(0) as f64;
+		let unit_value = hq_chance * hq_price + (1.0 - hq_chance) * nq_price;
+		let sale_value = unit_value * recipe.r#yield.unwrap_or(1) as f64;
+
+		Self {
+			materials: costs,
+			material_cost,
+			missing_prices,
+			intermediate_crafts: vec![],
+			hq_percent,
+			sale_value,
+			profit: sale_value - material_cost as f64,
+		}
+	}
+}
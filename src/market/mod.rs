@@ -0,0 +1,5 @@
+mod client;
+pub use client::*;
+
+mod estimate;
+pub use estimate::*;
@@ -0,0 +1,169 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::types::{
+	enums::CraftingJob,
+	structs::{Craft, Ingredient},
+};
+
+use super::RecipeDatabase;
+
+/// Finds the recipe crafting an item, to expand ingredients into intermediate crafts.
+pub trait RecipeLookup {
+	fn recipe_for_item(&self, item_id: &str) -> Option<&Craft>;
+}
+
+impl RecipeLookup for RecipeDatabase {
+	/// The first recipe of the item, for items more than one job can craft.
+	fn recipe_for_item(&self, item_id: &str) -> Option<&Craft> {
+		let item_id = item_id.parse().ok()?;
+		self.recipes_for_item(item_id).into_iter().next()
+	}
+}
+
+/// Recipes by the id of the item they craft.
+impl RecipeLookup for HashMap<String, Craft> {
+	fn recipe_for_item(&self, item_id: &str) -> Option<&Craft> {
+		self.get(item_id)
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CraftingListEntry {
+	pub item_id: String,
+	pub recipe_id: String,
+	pub job: CraftingJob,
+	/// How many times the recipe is crafted.
+	pub crafts: u32,
+	/// How many items are needed; `crafts` times the yield may be more.
+	pub amount: u32,
+}
+
+/// Everything needed to craft some items, from the raw materials up.
+#[derive(Clone)]
+pub struct CraftingList {
+	/// Materials without a recipe, and items a recipe loop needs again once they are
+	/// crafted, in the order they are first needed.
+	pub raw_materials: Vec<Ingredient>,
+	/// Every craft, each coming after the crafts of its ingredients.
+	pub crafts: Vec<CraftingListEntry>,
+}
+
+impl CraftingList {
+	/// Expands the given `(item id, quantity)` targets into their intermediate crafts.
+	///
+	/// The needs of every item are added up across the whole tree before being rounded
+	/// up to whole crafts, so a multi-yield intermediate used in several places isn't
+	/// crafted more often than needed. In a recipe loop, what an ingredient needs of the
+	/// item being crafted from it is bought as a raw material.
+	pub fn new(lookup: &impl RecipeLookup, targets: &[(&str, u32)]) -> Self {
+		// items with a recipe, each after every item using it
+		let mut order = vec![];
+		let mut visited = HashSet::new();
+		// visited backwards so that, reversed, the first target comes first
+		for &(item_id, _) in targets.iter().rev() {
+			visit(
+				lookup,
+				item_id,
+				&mut visited,
+				&mut HashSet::new(),
+				&mut order,
+			);
+		}
+		order.reverse();
+
+		let mut needed: HashMap<String, u32> = HashMap::new();
+		// items whose crafts are settled; later needs of them have to be bought
+		let mut crafted: HashSet<String> = HashSet::new();
+		let mut raw_materials: Vec<Ingredient> = vec![];
+		let mut add_need = |item_id: &str,
+		                    amount: u32,
+		                    needed: &mut HashMap<String, u32>,
+		                    crafted: &HashSet<String>| {
+			if order.iter().any(|id| id == item_id) && !crafted.contains(item_id) {
+				*needed.entry(item_id.to_string()).or_default() += amount;
+			} else {
+				match raw_materials
+					.iter_mut()
+					.find(|material| material.id == item_id)
+				{
+					Some(material) => material.amount += amount,
+					None => raw_materials.push(Ingredient {
+						id: item_id.to_string(),
+						amount,
+						..Default::default()
+					}),
+				}
+			}
+		};
+		for &(item_id, quantity) in targets {
+			add_need(item_id, quantity, &mut needed, &crafted);
+		}
+
+		let mut crafts = vec![];
+		for item_id in &order {
+			crafted.insert(item_id.clone());
+			let amount = needed.get(item_id).copied().unwrap_or(0);
+			let recipe = lookup.recipe_for_item(item_id).unwrap();
+			let recipe_yield = recipe.r#yield.unwrap_or(1).max(1);
+			let craft_count = (amount + recipe_yield - 1) / recipe_yield;
+			if craft_count == 0 {
+				continue;
+			}
+			for ingredient in &recipe.ingredients {
+				add_need(
+					&ingredient.id,
+					ingredient.amount * craft_count,
+					&mut needed,
+					&crafted,
+				);
+			}
+			crafts.push(CraftingListEntry {
+				item_id: item_id.clone(),
+				recipe_id: recipe.id.clone(),
+				job: CraftingJob::from_class_job_id(recipe.job),
+				crafts: craft_count,
+				amount,
+			});
+		}
+		crafts.reverse();
+
+		Self {
+			raw_materials,
+			crafts,
+		}
+	}
+
+	/// The crafts of each job, in crafting order.
+	pub fn crafts_by_job(&self) -> HashMap<CraftingJob, Vec<&CraftingListEntry>> {
+		let mut by_job: HashMap<_, Vec<_>> = HashMap::new();
+		for entry in &self.crafts {
+			by_job.entry(entry.job).or_default().push(entry);
+		}
+		by_job
+	}
+}
+
+/// Depth-first post-order over the items that have a recipe. An item already being
+/// expanded higher up (a recipe loop) isn't expanded again.
+fn visit(
+	lookup: &impl RecipeLookup,
+	item_id: &str,
+	visited: &mut HashSet<String>,
+	in_progress: &mut HashSet<String>,
+	order: &mut Vec<String>,
+) {
+	if visited.contains(item_id) || in_progress.contains(item_id) {
+		return;
+	}
+	let Some(recipe) = lookup.recipe_for_item(item_id) else {
+		return;
+	};
+	in_progress.insert(item_id.to_string());
+	for ingredient in &recipe.ingredients {
+		visit(lookup, &ingredient.id, visited, in_progress, order);
+	}
+	in_progress.remove(item_id);
+	visited.insert(item_id.to_string());
+	order.push(item_id.to_string());
+}
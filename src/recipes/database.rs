@@ -0,0 +1,335 @@
+use std::{
+	collections::{BTreeMap, HashMap},
+	fs::File,
+	io::Read,
+	path::Path,
+};
+
+use crate::types::{
+	structs::{Craft, CraftingLevel, Ingredient},
+	tables::{RecipeLevel, RecipeLevelTable},
+};
+
+use super::{
+	sheet::{Row, Sheet},
+	RecipeDataError,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Item {
+	pub id: u32,
+	pub name: String,
+	pub ilvl: u32,
+	pub can_be_hq: bool,
+}
+
+/// Recipes read from datamined Recipe, RecipeLevelTable and Item sheets.
+///
+/// Every recipe is turned into a ready to simulate [`Craft`], with its ingredients,
+/// requirements and level-derived values filled in.
+pub struct RecipeDatabase {
+	recipes: BTreeMap<u32, Craft>,
+	result_items: HashMap<u32, u32>,
+	recipes_by_item: HashMap<u32, Vec<u32>>,
+	items: HashMap<u32, Item>,
+	recipe_levels: RecipeLevelTable,
+}
+
+impl RecipeDatabase {
+	/// Loads `Recipe.csv`, `RecipeLevelTable.csv` and `Item.csv` from a directory.
+	pub fn load(dir: impl AsRef<Path>) -> Result<Self, RecipeDataError> {
+		let dir = dir.as_ref();
+		Self::from_readers(
+			File::open(dir.join("Recipe.csv"))?,
+			File::open(dir.join("RecipeLevelTable.csv"))?,
+			File::open(dir.join("Item.csv"))?,
+		)
+	}
+
+	pub fn from_readers(
+		recipe: impl Read,
+		recipe_level_table: impl Read,
+		item: impl Read,
+	) -> Result<Self, RecipeDataError> {
+		Self::from_sheets(
+			&Sheet::read("Recipe", recipe)?,
+			&Sheet::read("RecipeLevelTable", recipe_level_table)?,
+			&Sheet::read("Item", item)?,
+		)
+	}
+
+	pub(crate) fn from_sheets(
+		recipe: &Sheet,
+		recipe_level_table: &Sheet,
+		item: &Sheet,
+	) -> Result<Self, RecipeDataError> {
+		let items = read_items(item)?;
+		let levels = read_recipe_levels(recipe_level_table)?;
+
+		let mut database = Self {
+			recipes: BTreeMap::new(),
+			result_items: HashMap::new(),
+			recipes_by_item: HashMap::new(),
+			items,
+			recipe_levels: levels.values().map(|level| level.row).collect(),
+		};
+		database.read_recipes(recipe, &levels)?;
+		Ok(database)
+	}
+
+	pub fn recipe(&self, recipe_id: u32) -> Option<&Craft> {
+		self.recipes.get(&recipe_id)
+	}
+
+	/// Every recipe crafting the given item, e.g. one per job for shared items.
+	pub fn recipes_for_item(&self, item_id: u32) -> Vec<&Craft> {
+		self.recipes_by_item
+			.get(&item_id)
+			.into_iter()
+			.flatten()
+			.filter_map(|recipe_id| self.recipes.get(recipe_id))
+			.collect()
+	}
+
+	/// Every recipe crafting the item with the given name, ignoring case.
+	pub fn recipes_for_item_name(&self, name: &str) -> Vec<&Craft> {
+		self.item_by_name(name)
+			.map(|item| self.recipes_for_item(item.id))
+			.unwrap_or_default()
+	}
+
+	pub fn recipes(&self) -> impl Iterator<Item = &Craft> {
+		self.recipes.values()
+	}
+
+	/// The item crafted by the given recipe.
+	pub fn result_item(&self, recipe_id: u32) -> Option<&Item> {
+		self.result_items
+			.get(&recipe_id)
+			.and_then(|item_id| self.items.get(item_id))
+	}
+
+	pub fn item(&self, item_id: u32) -> Option<&Item> {
+		self.items.get(&item_id)
+	}
+
+	pub fn item_by_name(&self, name: &str) -> Option<&Item> {
+		let name = name.trim().to_lowercase();
+		self.items
+			.values()
+			.find(|item| item.name.to_lowercase() == name)
+	}
+
+	pub fn recipe_level_table(&self) -> &RecipeLevelTable {
+		&self.recipe_levels
+	}
+
+	fn read_recipes(
+		&mut self,
+		sheet: &Sheet,
+		levels: &HashMap<u32, RecipeLevelRow>,
+	) -> Result<(), RecipeDataError> {
+		for row in sheet.rows() {
+			let id = row.u32(&["#", "key"])?;
+			let item_id = row.u32(&["Item{Result}", "ItemResult"])?;
+			let rlvl = row.u32(&["RecipeLevelTable"])?;
+			let Some(level) = levels.get(&rlvl) else {
+				// unused rows are left empty
+				continue;
+			};
+			if item_id == 0 {
+				continue;
+			}
+
+			let mut ingredients = vec![];
+			for ix in 0.. {
+				let item_columns = [
+					format!("Item{{Ingredient}}[{}]", ix),
+					format!("Ingredient[{}]", ix),
+				];
+				let amount_columns = [
+					format!("Amount{{Ingredient}}[{}]", ix),
+					format!("AmountIngredient[{}]", ix),
+				];
+				let item_columns: Vec<_> = item_columns.iter().map(String::as_str).collect();
+				let amount_columns: Vec<_> = amount_columns.iter().map(String::as_str).collect();
+				let Some(ingredient_id) = row.optional_u32(&item_columns)? else {
+					break;
+				};
+				let amount = row.u32(&amount_columns)?;
+				if ingredient_id > 0 && amount > 0 {
+					ingredients.push((ingredient_id, amount));
+				}
+			}
+
+			let factor = |value: u32, factor: u32| value * factor / 100;
+			let progress = factor(level.difficulty, row.u32(&["DifficultyFactor"])?);
+			let quality = factor(level.quality, row.u32(&["QualityFactor"])?);
+			let durability = factor(level.durability, row.u32(&["DurabilityFactor"])?);
+			let material_quality_factor = row.u32(&["MaterialQualityFactor"])?;
+			let nonzero = |value: u32| (value > 0).then_some(value);
+
+			let recipe = Craft {
+				id: id.to_string(),
+				// ClassJob ids of the crafting jobs start at 8 (Carpenter)
+				job: row.u32(&["CraftType"])? + 8,
+				rlvl,
+				durability,
+				quality,
+				progress,
+				lvl: level.row.class_job_level,
+				suggested_craftsmanship: level.suggested_craftsmanship,
+				suggested_control: level.suggested_control,
+				stars: nonzero(level.row.stars),
+				hq: Some(row.bool(&["CanHq"])?),
+				quick_synth: Some(row.bool(&["CanQuickSynth"])?),
+				control_req: nonzero(row.u32(&["RequiredControl"])?),
+				craftsmanship_req: nonzero(row.u32(&["RequiredCraftsmanship"])?),
+				unlock_id: nonzero(row.u32(&["SecretRecipeBook"])?),
+				ingredients: self.ingredients(&ingredients, quality, material_quality_factor),
+				r#yield: Some(row.u32(&["Amount{Result}", "AmountResult"])?.max(1)),
+				expert: Some(row.bool(&["IsExpert"])?),
+				conditions_flag: level.row.conditions_flag,
+				progress_divider: level.row.progress_divider,
+				quality_divider: level.row.quality_divider,
+				progress_modifier: Some(level.row.progress_modifier as f64),
+				quality_modifier: Some(level.row.quality_modifier as f64),
+				required_quality: nonzero(row.u32(&["RequiredQuality"])?),
+				material_quality_factor: Some(material_quality_factor),
+				// thresholds come from the turn-in sheets rather than the recipe
+				collectable_tiers: None,
+			};
+
+			self.recipes.insert(id, recipe);
+			self.result_items.insert(id, item_id);
+			self.recipes_by_item.entry(item_id).or_default().push(id);
+		}
+		Ok(())
+	}
+
+	/// Builds the ingredient list, splitting the quality that HQ ingredients can add
+	/// (`material_quality_factor` percent of the recipe's quality) between the
+	/// HQ-able ingredients in proportion to their item level.
+	fn ingredients(
+		&self,
+		ingredients: &[(u32, u32)],
+		quality: u32,
+		material_quality_factor: u32,
+	) -> Vec<Ingredient> {
+		let hq_ilvl = |item_id: u32| {
+			self.items
+				.get(&item_id)
+				.filter(|item| item.can_be_hq)
+				.map(|item| item.ilvl)
+		};
+		let total_ilvl: u32 = ingredients
+			.iter()
+			.filter_map(|&(item_id, amount)| hq_ilvl(item_id).map(|ilvl| ilvl * amount))
+			.sum();
+		let max_quality = quality as f64 * material_quality_factor as f64 / 100.0;
+
+		ingredients
+			.iter()
+			.map(|&(item_id, amount)| Ingredient {
+				id: item_id.to_string(),
+				amount,
+				quality: hq_ilvl(item_id)
+					.filter(|_| total_ilvl > 0)
+					.map(|ilvl| (max_quality * ilvl as f64 / total_ilvl as f64) as u32),
+				ilvl: hq_ilvl(item_id),
+				step_id: None,
+				part: None,
+				phase: None,
+				custom: None,
+			})
+			.collect()
+	}
+}
+
+/// A RecipeLevelTable row, along with the base values that recipes scale by their factors.
+struct RecipeLevelRow {
+	row: RecipeLevel,
+	difficulty: u32,
+	quality: u32,
+	durability: u32,
+	suggested_craftsmanship: Option<u32>,
+	suggested_control: Option<u32>,
+}
+
+fn read_items(sheet: &Sheet) -> Result<HashMap<u32, Item>, RecipeDataError> {
+	let mut items = HashMap::new();
+	for row in sheet.rows() {
+		let id = row.u32(&["#", "key"])?;
+		let name = row.str(&["Name", "Singular"])?;
+		if name.is_empty() {
+			continue;
+		}
+		items.insert(
+			id,
+			Item {
+				id,
+				name: name.to_string(),
+				ilvl: row.u32(&["Level{Item}", "LevelItem"])?,
+				can_be_hq: row.bool(&["CanBeHq"])?,
+			},
+		);
+	}
+	Ok(items)
+}
+
+impl RecipeLevelTable {
+	/// Loads the game's `RecipeLevelTable.csv`.
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, RecipeDataError> {
+		Self::from_reader(File::open(path)?)
+	}
+
+	/// Reads the game's RecipeLevelTable sheet, in either supported CSV layout.
+	pub fn from_reader(reader: impl Read) -> Result<Self, RecipeDataError> {
+		let sheet = Sheet::read("RecipeLevelTable", reader)?;
+		sheet.rows().map(|row| read_recipe_level(&row)).collect()
+	}
+}
+
+fn read_recipe_levels(sheet: &Sheet) -> Result<HashMap<u32, RecipeLevelRow>, RecipeDataError> {
+	let mut rows = HashMap::new();
+	for row in sheet.rows() {
+		let level = read_recipe_level(&row)?;
+		rows.insert(
+			level.rlvl,
+			RecipeLevelRow {
+				row: level,
+				difficulty: row.u32(&["Difficulty"])?,
+				quality: row.u32(&["Quality"])?,
+				durability: row.u32(&["Durability"])?,
+				suggested_craftsmanship: row.optional_u32(&["SuggestedCraftsmanship"])?,
+				suggested_control: row.optional_u32(&["SuggestedControl"])?,
+			},
+		);
+	}
+	Ok(rows)
+}
+
+fn read_recipe_level(row: &Row) -> Result<RecipeLevel, RecipeDataError> {
+	let rlvl = row.u32(&["#", "key"])?;
+	let level = row.u32(&["ClassJobLevel"])?;
+	let class_job_level = u8::try_from(level)
+		.ok()
+		.and_then(CraftingLevel::new)
+		.ok_or_else(|| RecipeDataError::InvalidValue {
+			sheet: "RecipeLevelTable",
+			row: rlvl.to_string(),
+			column: "ClassJobLevel".to_string(),
+			value: level.to_string(),
+		})?;
+	Ok(RecipeLevel {
+		rlvl,
+		class_job_level,
+		stars: row.u32(&["Stars"])?,
+		progress_divider: row.u32(&["ProgressDivider"])?,
+		quality_divider: row.u32(&["QualityDivider"])?,
+		progress_modifier: row.u32(&["ProgressModifier"])?,
+		quality_modifier: row.u32(&["QualityModifier"])?,
+		conditions_flag: row.u32(&["ConditionsFlag"])?,
+	})
+}
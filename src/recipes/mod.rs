@@ -0,0 +1,7 @@
+mod crafting_list;
+mod database;
+pub use crafting_list::*;
+pub use database::*;
+
+pub(crate) mod sheet;
+pub use sheet::RecipeDataError;
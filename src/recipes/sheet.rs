@@ -0,0 +1,197 @@
+use std::{collections::HashMap, fmt, io::Read};
+
+/// A game data sheet exported as CSV.
+///
+/// Both the SaintCoinach layout (a `key` row of column indices, a row of column names
+/// and a row of column types before the data) and a plain single header row of column
+/// names, as written from EXDSchema definitions, are supported.
+pub(crate) struct Sheet {
+	name: &'static str,
+	columns: HashMap<String, usize>,
+	rows: Vec<csv::StringRecord>,
+}
+
+impl Sheet {
+	pub(crate) fn read(name: &'static str, reader: impl Read) -> Result<Self, RecipeDataError> {
+		let mut csv_reader = csv::ReaderBuilder::new()
+			.has_headers(false)
+			.flexible(true)
+			.from_reader(reader);
+		let mut records = csv_reader.records();
+
+		let mut header = records.next().transpose()?.unwrap_or_default();
+		let saint_coinach = header.get(0) == Some("key");
+		if saint_coinach {
+			header = records.next().transpose()?.unwrap_or_default();
+		}
+		let columns = header
+			.iter()
+			.enumerate()
+			.map(|(ix, column)| (column.trim().to_string(), ix))
+			.collect();
+
+		let mut rows = records.collect::<Result<Vec<_>, _>>()?;
+		if saint_coinach && !rows.is_empty() {
+			// the column types
+			rows.remove(0);
+		}
+
+		Ok(Self {
+			name,
+			columns,
+			rows,
+		})
+	}
+
+	/// Builds a sheet from rows of `(column, value)` pairs, e.g. taken from an API.
+	#[cfg(feature = "xivapi")]
+	pub(crate) fn from_fields(name: &'static str, rows: Vec<Vec<(String, String)>>) -> Self {
+		let mut columns = HashMap::new();
+		for (column, _) in rows.iter().flatten() {
+			let next_ix = columns.len();
+			columns.entry(column.clone()).or_insert(next_ix);
+		}
+		let rows = rows
+			.into_iter()
+			.map(|fields| {
+				let mut values = vec![String::new(); columns.len()];
+				for (column, value) in fields {
+					values[columns[&column]] = value;
+				}
+				csv::StringRecord::from(values)
+			})
+			.collect();
+
+		Self {
+			name,
+			columns,
+			rows,
+		}
+	}
+
+	pub(crate) fn rows(&self) -> impl Iterator<Item = Row<'_>> {
+		self.rows.iter().map(move |record| Row {
+			sheet: self,
+			record,
+		})
+	}
+
+	/// Finds the first of `names` that is a column of this sheet.
+	fn column(&self, names: &[&str]) -> Option<usize> {
+		names.iter().find_map(|name| self.columns.get(*name).copied())
+	}
+}
+
+pub(crate) struct Row<'a> {
+	sheet: &'a Sheet,
+	record: &'a csv::StringRecord,
+}
+
+impl<'a> Row<'a> {
+	/// The value of the first column found out of `names`, which are alternative
+	/// spellings of the same column.
+	pub(crate) fn str(&self, names: &[&str]) -> Result<&'a str, RecipeDataError> {
+		let ix = self
+			.sheet
+			.column(names)
+			.ok_or_else(|| RecipeDataError::MissingColumn {
+				sheet: self.sheet.name,
+				column: names[0].to_string(),
+			})?;
+		Ok(self.record.get(ix).unwrap_or("").trim())
+	}
+
+	pub(crate) fn u32(&self, names: &[&str]) -> Result<u32, RecipeDataError> {
+		let value = self.str(names)?;
+		if value.is_empty() {
+			return Ok(0);
+		}
+		value.parse().map_err(|_| self.invalid_value(names, value))
+	}
+
+	pub(crate) fn optional_u32(&self, names: &[&str]) -> Result<Option<u32>, RecipeDataError> {
+		match self.sheet.column(names) {
+			Some(_) => self.u32(names).map(Some),
+			None => Ok(None),
+		}
+	}
+
+	pub(crate) fn bool(&self, names: &[&str]) -> Result<bool, RecipeDataError> {
+		let value = self.str(names)?;
+		match value.to_lowercase().as_str() {
+			"true" | "1" => Ok(true),
+			"false" | "0" | "" => Ok(false),
+			_ => Err(self.invalid_value(names, value)),
+		}
+	}
+
+	fn invalid_value(&self, names: &[&str], value: &str) -> RecipeDataError {
+		RecipeDataError::InvalidValue {
+			sheet: self.sheet.name,
+			row: self.record.get(0).unwrap_or("").to_string(),
+			column: names[0].to_string(),
+			value: value.to_string(),
+		}
+	}
+}
+
+#[derive(Debug)]
+pub enum RecipeDataError {
+	Io(std::io::Error),
+	Csv(csv::Error),
+	MissingColumn {
+		sheet: &'static str,
+		column: String,
+	},
+	InvalidValue {
+		sheet: &'static str,
+		/// The row's key, i.e. its first column.
+		row: String,
+		column: String,
+		value: String,
+	},
+}
+
+impl fmt::Display for RecipeDataError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			RecipeDataError::Io(err) => write!(f, "{}", err),
+			RecipeDataError::Csv(err) => write!(f, "{}", err),
+			RecipeDataError::MissingColumn { sheet, column } => {
+				write!(f, "{}: missing column \"{}\"", sheet, column)
+			}
+			RecipeDataError::InvalidValue {
+				sheet,
+				row,
+				column,
+				value,
+			} => write!(
+				f,
+				"{} row {}: invalid value \"{}\" in column \"{}\"",
+				sheet, row, value, column
+			),
+		}
+	}
+}
+
+impl std::error::Error for RecipeDataError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			RecipeDataError::Io(err) => Some(err),
+			RecipeDataError::Csv(err) => Some(err),
+			_ => None,
+		}
+	}
+}
+
+impl From<std::io::Error> for RecipeDataError {
+	fn from(err: std::io::Error) -> Self {
+		RecipeDataError::Io(err)
+	}
+}
+
+impl From<csv::Error> for RecipeDataError {
+	fn from(err: csv::Error) -> Self {
+		RecipeDataError::Csv(err)
+	}
+}
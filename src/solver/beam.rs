@@ -0,0 +1,133 @@
+use bon::bon;
+use std::{collections::HashSet, sync::Arc};
+
+use crate::types::{
+	enums::{Buff, CraftingActionEnum},
+	structs::{Craft, CrafterStats, Ingredient},
+	Simulation, StateKey,
+};
+
+use super::{apply_action, guaranteed_actions};
+
+/// Scores a craft in progress; higher is better.
+pub type ScoreFn = Arc<dyn Fn(&Simulation) -> f64 + Send + Sync>;
+
+/// Builds a rotation step by step, only keeping the best few candidates
+/// (as ranked by a score function) after every step. One of them is always the
+/// candidate furthest along in progress, so that a rotation finishing the craft is
+/// always followed.
+///
+/// Like [`OptimalSolver`](super::OptimalSolver), every step is assumed to be
+/// Normal and only actions that can't fail are used. Finished crafts are ranked
+/// by quality (capped at the recipe's maximum), then by rotation length.
+pub struct BeamSearchSolver {
+	simulation: Simulation,
+	width: usize,
+	max_steps: usize,
+	score: ScoreFn,
+}
+
+#[bon]
+impl BeamSearchSolver {
+	#[builder]
+	pub fn new(
+		recipe: Craft,
+		crafter_stats: CrafterStats,
+		hq_ingredients: Option<Vec<Ingredient>>,
+		width: Option<usize>,
+		max_steps: Option<usize>,
+		score: Option<ScoreFn>,
+	) -> Self {
+		let simulation = Simulation::builder()
+			.recipe(recipe)
+			.crafter_stats(crafter_stats)
+			.maybe_hq_ingredients(hq_ingredients)
+			.build();
+
+		Self {
+			simulation,
+			width: width.unwrap_or(64).max(1),
+			max_steps: max_steps.unwrap_or(50),
+			score: score.unwrap_or_else(|| Arc::new(default_score)),
+		}
+	}
+
+	/// A search with the default score, for a craft already set up.
+	pub(super) fn from_simulation(simulation: Simulation, width: usize, max_steps: usize) -> Self {
+		Self {
+			simulation,
+			width,
+			max_steps,
+			score: Arc::new(default_score),
+		}
+	}
+
+	/// Returns the best complete rotation found, or `None` if none completed the craft.
+	pub fn solve(&self) -> Option<Vec<CraftingActionEnum>> {
+		let mut beam = vec![self.simulation.clone()];
+		let mut best: Option<Simulation> = None;
+
+		for _ in 0..self.max_steps {
+			let mut seen = HashSet::new();
+			let mut candidates = vec![];
+			for sim in &beam {
+				for action in guaranteed_actions(sim) {
+					let mut next = sim.clone();
+					apply_action(&mut next, &action);
+					match next.success {
+						Some(true) => {
+							if best.as_ref().map_or(true, |b| Self::is_better(&next, b)) {
+								best = Some(next);
+							}
+						}
+						Some(false) => (),
+						None => {
+							if seen.insert(StateKey::from(&next)) {
+								candidates.push(((self.score)(&next), next));
+							}
+						}
+					}
+				}
+			}
+
+			candidates.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+			// the highest scores can all be quality lines with nothing left to finish with
+			let furthest = candidates
+				.iter()
+				.enumerate()
+				.max_by_key(|(_, (_, sim))| (sim.progression, sim.durability, sim.available_cp))
+				.map(|(index, _)| index);
+			if let Some(furthest) = furthest.filter(|&index| index >= self.width) {
+				candidates.swap(self.width - 1, furthest);
+			}
+			candidates.truncate(self.width);
+			beam = candidates.into_iter().map(|(_, sim)| sim).collect();
+			if beam.is_empty() {
+				break;
+			}
+		}
+
+		best.map(|sim| sim.steps.iter().map(|step| step.action).collect())
+	}
+
+	fn is_better(a: &Simulation, b: &Simulation) -> bool {
+		let quality_a = a.quality.min(a.recipe.quality);
+		let quality_b = b.quality.min(b.recipe.quality);
+		quality_a > quality_b || (quality_a == quality_b && a.steps.len() < b.steps.len())
+	}
+}
+
+/// The default score, favouring quality, then progress, then leftover resources.
+pub fn default_score(sim: &Simulation) -> f64 {
+	let ratio = |value: f64, max: f64| if max > 0.0 { (value / max).min(1.0) } else { 1.0 };
+
+	let quality = ratio(sim.quality as f64, sim.recipe.quality as f64);
+	let progress = ratio(sim.progression as f64, sim.recipe.progress as f64);
+	let cp = ratio(sim.available_cp as f64, sim.max_cp as f64);
+	let durability = ratio(sim.durability.max(0) as f64, sim.recipe.durability as f64);
+	let inner_quiet = sim
+		.get_buff(Buff::InnerQuiet)
+		.map_or(0.0, |b| b.stacks as f64 / 10.0);
+
+	4.0 * quality + 2.0 * progress + cp + durability + 0.5 * inner_quiet
+}
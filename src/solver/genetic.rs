@@ -0,0 +1,248 @@
+use bon::bon;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::sync::Arc;
+
+use crate::types::{
+	enums::CraftingActionEnum,
+	structs::{Craft, CrafterStats, Ingredient, SimulationResult},
+	traits::CraftingAction,
+	Simulation,
+};
+
+/// Scores the result of a rotation; higher is better.
+pub type FitnessFn = Arc<dyn Fn(&SimulationResult) -> f64 + Send + Sync>;
+
+/// Evolves a population of rotations with mutation, crossover and tournament
+/// selection, in the spirit of the original teamcraft solver.
+///
+/// Rotations are evaluated in linear safe mode, so the best rotation found never
+/// relies on a lucky roll. Given a seed, the whole run is deterministic.
+pub struct GeneticSolver {
+	simulation: Simulation,
+	pool: Vec<CraftingActionEnum>,
+	initial_rotation: Option<Vec<CraftingActionEnum>>,
+	population_size: usize,
+	generations: usize,
+	tournament_size: usize,
+	mutation_rate: f64,
+	crossover_rate: f64,
+	max_length: usize,
+	seed: Option<u64>,
+	fitness: FitnessFn,
+}
+
+#[bon]
+impl GeneticSolver {
+	/// * `mutation_rate`, `crossover_rate`: chances from 0 to 1 per child, others being
+	///   clamped into that range
+	#[builder]
+	pub fn new(
+		recipe: Craft,
+		crafter_stats: CrafterStats,
+		hq_ingredients: Option<Vec<Ingredient>>,
+		initial_rotation: Option<Vec<CraftingActionEnum>>,
+		population_size: Option<usize>,
+		generations: Option<usize>,
+		tournament_size: Option<usize>,
+		mutation_rate: Option<f64>,
+		crossover_rate: Option<f64>,
+		max_length: Option<usize>,
+		seed: Option<u64>,
+		fitness: Option<FitnessFn>,
+	) -> Self {
+		let pool = CraftingActionEnum::ALL
+			.into_iter()
+			.filter(|action| crafter_stats.level >= action.get_level_requirement().1)
+			.collect();
+		let simulation = Simulation::builder()
+			.recipe(recipe)
+			.crafter_stats(crafter_stats)
+			.maybe_hq_ingredients(hq_ingredients)
+			.build();
+
+		Self {
+			simulation,
+			pool,
+			initial_rotation,
+			population_size: population_size.unwrap_or(200).max(2),
+			generations: generations.unwrap_or(500),
+			tournament_size: tournament_size.unwrap_or(3).max(1),
+			mutation_rate: probability(mutation_rate.unwrap_or(0.5)),
+			crossover_rate: probability(crossover_rate.unwrap_or(0.7)),
+			max_length: max_length.unwrap_or(50).max(1),
+			seed,
+			fitness: fitness.unwrap_or_else(|| Arc::new(default_fitness)),
+		}
+	}
+
+	/// Returns the fittest rotation that completes the craft, if one was found.
+	pub fn solve(&self) -> Option<Vec<CraftingActionEnum>> {
+		// no action is unlocked below level 1
+		if self.pool.is_empty() {
+			return None;
+		}
+		let mut rng = match self.seed {
+			Some(seed) => StdRng::seed_from_u64(seed),
+			None => StdRng::from_entropy(),
+		};
+
+		let mut population: Vec<Vec<CraftingActionEnum>> = vec![];
+		if let Some(initial_rotation) = &self.initial_rotation {
+			population.push(initial_rotation.clone());
+			while population.len() < self.population_size / 2 {
+				let mut rotation = initial_rotation.clone();
+				self.mutate(&mut rotation, &mut rng);
+				population.push(rotation);
+			}
+		}
+		while population.len() < self.population_size {
+			let length = rng.gen_range(1..=self.max_length);
+			population.push((0..length).map(|_| self.random_action(&mut rng)).collect());
+		}
+
+		let mut scored = self.evaluate(population);
+		for _ in 0..self.generations {
+			// the fittest rotation always survives
+			let mut next_generation = vec![scored[0].1.clone()];
+			while next_generation.len() < self.population_size {
+				let mut child = self.tournament(&scored, &mut rng).clone();
+				if rng.gen_bool(self.crossover_rate) {
+					let other = self.tournament(&scored, &mut rng);
+					child = self.crossover(&child, other, &mut rng);
+				}
+				if rng.gen_bool(self.mutation_rate) {
+					self.mutate(&mut child, &mut rng);
+				}
+				next_generation.push(child);
+			}
+			scored = self.evaluate(next_generation);
+		}
+
+		let (_, best) = scored.into_iter().next()?;
+		let result = self.run(&best);
+		if !result.success {
+			return None;
+		}
+		// anything after the craft completed is skipped, so it can be dropped
+		let used_steps = result
+			.steps
+			.iter()
+			.rposition(|step| step.success.is_some())
+			.map_or(0, |ix| ix + 1);
+		Some(best[..used_steps].to_vec())
+	}
+
+	fn run(&self, rotation: &[CraftingActionEnum]) -> SimulationResult {
+		let mut sim = self.simulation.clone();
+		sim.actions = rotation.to_vec();
+		sim.start().linear(true).safe(true).run()
+	}
+
+	/// Scores every rotation, sorting them from fittest to least fit.
+	fn evaluate(
+		&self,
+		population: Vec<Vec<CraftingActionEnum>>,
+	) -> Vec<(f64, Vec<CraftingActionEnum>)> {
+		let mut scored: Vec<_> = population
+			.into_iter()
+			.map(|rotation| ((self.fitness)(&self.run(&rotation)), rotation))
+			.collect();
+		scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+		scored
+	}
+
+	fn tournament<'a>(
+		&self,
+		scored: &'a [(f64, Vec<CraftingActionEnum>)],
+		rng: &mut StdRng,
+	) -> &'a Vec<CraftingActionEnum> {
+		// the population is sorted, so the lowest index drawn is the fittest
+		let winner = (0..self.tournament_size)
+			.map(|_| rng.gen_range(0..scored.len()))
+			.min()
+			.unwrap();
+		&scored[winner].1
+	}
+
+	fn crossover(
+		&self,
+		a: &[CraftingActionEnum],
+		b: &[CraftingActionEnum],
+		rng: &mut StdRng,
+	) -> Vec<CraftingActionEnum> {
+		let cut_a = rng.gen_range(0..=a.len());
+		let cut_b = rng.gen_range(0..=b.len());
+		let mut child: Vec<_> = a[..cut_a].iter().chain(&b[cut_b..]).copied().collect();
+		child.truncate(self.max_length);
+		if child.is_empty() {
+			child.push(self.random_action(rng));
+		}
+		child
+	}
+
+	fn mutate(&self, rotation: &mut Vec<CraftingActionEnum>, rng: &mut StdRng) {
+		match rng.gen_range(0..4) {
+			// insert
+			0 if rotation.len() < self.max_length => {
+				let ix = rng.gen_range(0..=rotation.len());
+				rotation.insert(ix, self.random_action(rng));
+			}
+			// delete
+			1 if rotation.len() > 1 => {
+				rotation.remove(rng.gen_range(0..rotation.len()));
+			}
+			// swap
+			2 if rotation.len() > 1 => {
+				let a = rng.gen_range(0..rotation.len());
+				let b = rng.gen_range(0..rotation.len());
+				rotation.swap(a, b);
+			}
+			// replace
+			_ if !rotation.is_empty() => {
+				let ix = rng.gen_range(0..rotation.len());
+				rotation[ix] = self.random_action(rng);
+			}
+			_ => rotation.push(self.random_action(rng)),
+		}
+	}
+
+	fn random_action(&self, rng: &mut StdRng) -> CraftingActionEnum {
+		*self.pool.choose(rng).unwrap()
+	}
+}
+
+/// A rate as a chance that can be drawn, NaN counting as never.
+fn probability(rate: f64) -> f64 {
+	if rate.is_nan() {
+		0.0
+	} else {
+		rate.clamp(0.0, 1.0)
+	}
+}
+
+/// The default fitness: completing the craft outweighs everything else, then quality,
+/// with small bonuses for leftover CP and short rotations without wasted steps.
+pub fn default_fitness(result: &SimulationResult) -> f64 {
+	let sim = &result.simulation;
+	let ratio = |value: f64, max: f64| if max > 0.0 { (value / max).min(1.0) } else { 1.0 };
+
+	let progress = ratio(sim.progression as f64, sim.recipe.progress as f64);
+	let quality = ratio(sim.quality as f64, sim.recipe.quality as f64);
+	let cp = ratio(sim.available_cp as f64, sim.max_cp as f64);
+	let used_steps = result
+		.steps
+		.iter()
+		.filter(|step| step.success.is_some())
+		.count();
+	let wasted_steps = result
+		.steps
+		.iter()
+		.filter(|step| step.fail_cause.is_some() || step.success == Some(false))
+		.count();
+
+	let mut fitness = progress + quality - 0.001 * used_steps as f64 - 0.1 * wasted_steps as f64;
+	if result.success {
+		fitness += 2.0 + 0.01 * cp;
+	}
+	fitness
+}
@@ -0,0 +1,150 @@
+use bon::bon;
+use std::collections::HashMap;
+
+use crate::types::{
+	enums::CraftingActionEnum,
+	structs::{Craft, CrafterStats, Ingredient, SimulationResult},
+	Simulation,
+};
+
+/// Picks which ingredients to use in HQ so that a rotation reaches a quality target for
+/// the least money.
+///
+/// The quality a rotation adds doesn't depend on the quality it starts from, so the
+/// rotation is simulated once, without HQ ingredients, and the remaining quality is
+/// covered by the cheapest mix of HQ ingredients.
+pub struct HqIngredientSolver {
+	recipe: Craft,
+	crafter_stats: CrafterStats,
+	actions: Vec<CraftingActionEnum>,
+	hq_costs: HashMap<String, u64>,
+	target_quality: u32,
+}
+
+#[bon]
+impl HqIngredientSolver {
+	/// * `hq_costs`: extra cost of each ingredient in HQ rather than NQ, by ingredient id;
+	///   ingredients without a cost are always used in NQ
+	/// * `target_quality`: the quality to reach, the recipe's maximum quality (i.e. 100% HQ)
+	///   by default
+	#[builder]
+	pub fn new(
+		recipe: Craft,
+		crafter_stats: CrafterStats,
+		actions: Vec<CraftingActionEnum>,
+		hq_costs: HashMap<String, u64>,
+		target_quality: Option<u32>,
+	) -> Self {
+		Self {
+			target_quality: target_quality.unwrap_or(recipe.quality),
+			recipe,
+			crafter_stats,
+			actions,
+			hq_costs,
+		}
+	}
+
+	/// Returns the cheapest HQ ingredients reaching the target, or `None` if even every
+	/// available HQ ingredient isn't enough.
+	pub fn solve(&self) -> Option<HqIngredientPlan> {
+		let rotation_quality = self.run(vec![]).simulation.quality;
+		let needed_quality = self.target_quality.saturating_sub(rotation_quality);
+
+		// every HQ item available, as (ingredient index, weight, cost)
+		let units: Vec<_> = self
+			.recipe
+			.ingredients
+			.iter()
+			.enumerate()
+			.filter_map(|(ix, ingredient)| {
+				let cost = *self.hq_costs.get(&ingredient.id)?;
+				let weight = self.recipe.hq_weight(ingredient) as usize;
+				(weight > 0).then_some((ix, weight, cost, ingredient.amount))
+			})
+			.flat_map(|(ix, weight, cost, amount)| (0..amount).map(move |_| (ix, weight, cost)))
+			.collect();
+
+		// cheapest choice of HQ items for every total weight, as a bounded knapsack
+		let total_weight: usize = units.iter().map(|&(_, weight, _)| weight).sum();
+		let mut cheapest: Vec<Option<(u64, Vec<u32>)>> = vec![None; total_weight + 1];
+		cheapest[0] = Some((0, vec![0; self.recipe.ingredients.len()]));
+		for &(ix, weight, cost) in &units {
+			for total in (weight..=total_weight).rev() {
+				let Some((previous_cost, previous_counts)) = &cheapest[total - weight] else {
+					continue;
+				};
+				let new_cost = previous_cost + cost;
+				if cheapest[total]
+					.as_ref()
+					.map_or(true, |(current_cost, _)| new_cost < *current_cost)
+				{
+					let mut counts = previous_counts.clone();
+					counts[ix] += 1;
+					cheapest[total] = Some((new_cost, counts));
+				}
+			}
+		}
+
+		let mut candidates: Vec<_> = cheapest
+			.into_iter()
+			.enumerate()
+			.filter_map(|(total, choice)| choice.map(|(cost, counts)| (cost, total, counts)))
+			.collect();
+		candidates.sort_by_key(|&(cost, total, _)| (cost, total));
+
+		candidates.into_iter().find_map(|(cost, _, counts)| {
+			let hq_ingredients: Vec<_> = self
+				.recipe
+				.ingredients
+				.iter()
+				.zip(counts)
+				.filter(|&(_, count)| count > 0)
+				.map(|(ingredient, count)| Ingredient {
+					id: ingredient.id.clone(),
+					amount: count,
+					..Default::default()
+				})
+				.collect();
+			let initial_quality = self.recipe.initial_quality(&hq_ingredients);
+			if initial_quality < needed_quality {
+				return None;
+			}
+
+			let result = self.run(hq_ingredients.clone());
+			Some(HqIngredientPlan {
+				hq_ingredients,
+				cost,
+				initial_quality,
+				quality: result.simulation.quality,
+				hq_percent: result.hq_percent,
+				success: result.success,
+			})
+		})
+	}
+
+	fn run(&self, hq_ingredients: Vec<Ingredient>) -> SimulationResult {
+		Simulation::builder()
+			.recipe(self.recipe.clone())
+			.crafter_stats(self.crafter_stats.clone())
+			.actions(self.actions.clone())
+			.hq_ingredients(hq_ingredients)
+			.build()
+			.start()
+			.linear(true)
+			.run()
+	}
+}
+
+#[derive(Clone)]
+pub struct HqIngredientPlan {
+	/// How many of each ingredient to use in HQ.
+	pub hq_ingredients: Vec<Ingredient>,
+	/// Total extra cost of the HQ ingredients.
+	pub cost: u64,
+	pub initial_quality: u32,
+	/// Quality at the end of the rotation.
+	pub quality: u32,
+	pub hq_percent: u32,
+	/// Whether the rotation completes the craft (and meets any required quality).
+	pub success: bool,
+}
@@ -0,0 +1,262 @@
+use bon::bon;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::{
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+use crate::types::{
+	enums::{CraftingActionEnum, StepState},
+	structs::Craft,
+	traits::CraftingAction,
+	Simulation,
+};
+
+/// Scores a finished (or abandoned) craft, from `0.0` (failed) to `1.0` (perfect).
+pub type RewardFn = Arc<dyn Fn(&Simulation) -> f64 + Send + Sync>;
+
+/// Recommends the next action of a craft in progress using Monte Carlo tree search.
+///
+/// Every iteration samples its own success rolls and condition changes (through
+/// [`Simulation::tick_state`]), so the recommendation accounts for the random
+/// conditions of expert recipes instead of assuming a fixed sequence.
+pub struct MctsPlanner {
+	simulation: Simulation,
+	iterations: Option<usize>,
+	time_limit: Option<Duration>,
+	exploration: f64,
+	max_steps: usize,
+	seed: Option<u64>,
+	reward: RewardFn,
+}
+
+#[bon]
+impl MctsPlanner {
+	/// `simulation` is the craft so far; `state` overrides its current condition.
+	///
+	/// Without an iteration count or a time limit, 1000 iterations are run.
+	#[builder]
+	pub fn new(
+		simulation: Simulation,
+		state: Option<StepState>,
+		iterations: Option<usize>,
+		time_limit: Option<Duration>,
+		exploration: Option<f64>,
+		max_steps: Option<usize>,
+		seed: Option<u64>,
+		reward: Option<RewardFn>,
+	) -> Self {
+		let mut simulation = simulation;
+		if let Some(state) = state {
+			simulation.override_state(state);
+		}
+
+		Self {
+			simulation,
+			iterations: if iterations.is_none() && time_limit.is_none() {
+				Some(1000)
+			} else {
+				iterations
+			},
+			time_limit,
+			exploration: exploration.unwrap_or(std::f64::consts::SQRT_2),
+			max_steps: max_steps.unwrap_or(50),
+			seed,
+			reward: reward.unwrap_or_else(|| Arc::new(default_reward)),
+		}
+	}
+
+	pub fn plan(&self) -> MctsResult {
+		let mut rng = match self.seed {
+			Some(seed) => StdRng::seed_from_u64(seed),
+			None => StdRng::from_entropy(),
+		};
+		let mut tree = vec![Node::default()];
+		let started = Instant::now();
+
+		let mut iteration = 0;
+		while self.iterations.map_or(true, |limit| iteration < limit)
+			&& self.time_limit.map_or(true, |limit| started.elapsed() < limit)
+		{
+			self.iterate(&mut tree, &mut rng);
+			iteration += 1;
+		}
+
+		let mut actions: Vec<_> = tree[0]
+			.children
+			.iter()
+			.map(|&(action, ix)| ActionStats {
+				action,
+				visits: tree[ix].visits,
+				value: tree[ix].mean(),
+			})
+			.collect();
+		actions.sort_by(|a, b| b.visits.cmp(&a.visits).then(b.value.total_cmp(&a.value)));
+		MctsResult {
+			iterations: iteration,
+			actions,
+		}
+	}
+
+	fn iterate(&self, tree: &mut Vec<Node>, rng: &mut StdRng) {
+		let mut sim = self.simulation.clone();
+		sim.reseed(rng.gen());
+		let mut path = vec![0];
+		let mut node = 0;
+
+		// selection and expansion
+		while !self.is_finished(&sim) {
+			let legal = legal_actions(&sim);
+			if legal.is_empty() {
+				break;
+			}
+			let untried: Vec<_> = legal
+				.iter()
+				.filter(|action| !tree[node].children.iter().any(|(a, _)| a == *action))
+				.collect();
+			if let Some(&&action) = untried.choose(rng) {
+				tree.push(Node::default());
+				let child = tree.len() - 1;
+				tree[node].children.push((action, child));
+				step(&mut sim, &action);
+				path.push(child);
+				break;
+			}
+
+			let parent_visits = tree[node].visits.max(1) as f64;
+			let (action, child) = *tree[node]
+				.children
+				.iter()
+				.filter(|(action, _)| legal.contains(action))
+				.max_by(|(_, a), (_, b)| {
+					let uct = |ix: usize| {
+						tree[ix].mean()
+							+ self.exploration
+								* (parent_visits.ln() / tree[ix].visits.max(1) as f64).sqrt()
+					};
+					uct(*a).total_cmp(&uct(*b))
+				})
+				.unwrap();
+			step(&mut sim, &action);
+			path.push(child);
+			node = child;
+		}
+
+		// random rollout
+		while !self.is_finished(&sim) {
+			let legal = legal_actions(&sim);
+			match legal.choose(rng) {
+				Some(action) => step(&mut sim, action),
+				None => break,
+			}
+		}
+
+		let reward = (self.reward)(&sim);
+		for ix in path {
+			tree[ix].visits += 1;
+			tree[ix].total_reward += reward;
+		}
+	}
+
+	fn is_finished(&self, sim: &Simulation) -> bool {
+		sim.success.is_some() || sim.steps.len() >= self.max_steps
+	}
+}
+
+#[derive(Default)]
+struct Node {
+	visits: u32,
+	total_reward: f64,
+	children: Vec<(CraftingActionEnum, usize)>,
+}
+
+impl Node {
+	fn mean(&self) -> f64 {
+		if self.visits == 0 {
+			0.0
+		} else {
+			self.total_reward / self.visits as f64
+		}
+	}
+}
+
+fn legal_actions(sim: &Simulation) -> Vec<CraftingActionEnum> {
+	CraftingActionEnum::ALL
+		.into_iter()
+		.filter(|action| {
+			action.can_be_used_with_flags(sim, Some(false), Some(false))
+				&& action.get_base_cp_cost(sim) <= sim.available_cp
+		})
+		.collect()
+}
+
+/// Uses an action with a random success roll, then moves on to the next condition.
+fn step(sim: &mut Simulation, action: &CraftingActionEnum) {
+	let result = sim.run_step(action, sim.steps.len(), false, false, usize::MAX, None);
+	if Simulation::ticks_state(action) {
+		sim.tick_state();
+	}
+	sim.steps.push(result);
+}
+
+/// The default reward, aiming for the quality the recipe asks for (see
+/// [`quality_target`]).
+pub fn default_reward(sim: &Simulation) -> f64 {
+	target_reward(sim, quality_target(&sim.recipe))
+}
+
+/// A reward aiming for the given quality, so that going past it is worth nothing more.
+pub fn quality_target_reward(target: u32) -> RewardFn {
+	Arc::new(move |sim| target_reward(sim, target))
+}
+
+/// The quality worth reaching on a recipe: just over its required quality, the
+/// collectability of its best tier, or otherwise its maximum.
+pub fn quality_target(recipe: &Craft) -> u32 {
+	let target = match (recipe.required_quality, &recipe.collectable_tiers) {
+		(Some(required_quality), _) => required_quality + 1,
+		(None, Some(tiers)) if !tiers.is_empty() => tiers
+			.iter()
+			.map(|tier| tier.collectability * 10)
+			.max()
+			.unwrap_or(0),
+		_ => recipe.quality,
+	};
+	target.min(recipe.quality)
+}
+
+/// Nothing for an unfinished craft, then half for completing it and the other half in
+/// proportion to quality, up to `target`.
+fn target_reward(sim: &Simulation, target: u32) -> f64 {
+	if sim.progression < sim.recipe.progress {
+		return 0.0;
+	}
+	let quality = if target > 0 {
+		(sim.quality as f64 / target as f64).min(1.0)
+	} else {
+		1.0
+	};
+	0.5 + 0.5 * quality
+}
+
+#[derive(Clone)]
+pub struct ActionStats {
+	pub action: CraftingActionEnum,
+	pub visits: u32,
+	/// Mean reward of the iterations that started with this action.
+	pub value: f64,
+}
+
+#[derive(Clone)]
+pub struct MctsResult {
+	pub iterations: usize,
+	/// Statistics for each action tried from the current step, most visited first.
+	pub actions: Vec<ActionStats>,
+}
+
+impl MctsResult {
+	/// The recommended next action, i.e. the most visited one.
+	pub fn best_action(&self) -> Option<CraftingActionEnum> {
+		self.actions.first().map(|stats| stats.action)
+	}
+}
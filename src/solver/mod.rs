@@ -0,0 +1,43 @@
+mod optimal;
+pub use optimal::*;
+
+mod beam;
+pub use beam::*;
+
+mod genetic;
+pub use genetic::*;
+
+mod mcts;
+pub use mcts::*;
+
+mod hq_ingredients;
+pub use hq_ingredients::*;
+
+use crate::types::{enums::CraftingActionEnum, traits::CraftingAction, Simulation};
+
+/// The actions that can be used next and are guaranteed to succeed,
+/// assuming every step is in the Normal condition.
+///
+/// Actions that only interact with conditions (or with Final Appraisal, which only
+/// matters when the next condition is unknown) are left out.
+pub(crate) fn guaranteed_actions(sim: &Simulation) -> Vec<CraftingActionEnum> {
+	CraftingActionEnum::ALL
+		.into_iter()
+		.filter(|action| {
+			!matches!(
+				action,
+				CraftingActionEnum::CarefulObservation(_)
+					| CraftingActionEnum::FinalAppraisal(_)
+					| CraftingActionEnum::RemoveFinalAppraisal(_)
+			) && action.can_be_used_with_flags(sim, Some(false), Some(false))
+				&& action.get_base_cp_cost(sim) <= sim.available_cp
+				&& action.get_success_rate(sim) >= 100
+		})
+		.collect()
+}
+
+/// Uses an action as the next step, forcing it to succeed and keeping the Normal condition.
+pub(crate) fn apply_action(sim: &mut Simulation, action: &CraftingActionEnum) {
+	let result = sim.run_step(action, sim.steps.len(), false, false, usize::MAX, Some(0));
+	sim.steps.push(result);
+}
@@ -0,0 +1,502 @@
+use bon::bon;
+use std::{cmp::Reverse, collections::HashMap};
+
+use crate::types::{
+	actions,
+	enums::{Buff, CraftingActionEnum},
+	structs::{Craft, CrafterStats, Ingredient},
+	traits::CraftingAction,
+	Simulation, StateKey,
+};
+
+use super::{apply_action, guaranteed_actions, BeamSearchSolver};
+
+/// Finds the rotation that completes a craft with the highest possible quality,
+/// assuming every step is in the Normal condition and only using actions that
+/// can't fail.
+///
+/// The search is a depth-first branch and bound over deduplicated states: the most
+/// promising actions are tried first, and states whose quality can't beat the best
+/// rotation found so far (see [`QualityBound`]) are left out. `max_steps` only cuts the
+/// search off.
+///
+/// Proving a rotation the best can take too long on a real recipe, so the search can be
+/// stopped after `max_states` states to settle for the best rotation found by then (see
+/// [`OptimalSolution::optimal`]).
+pub struct OptimalSolver {
+	simulation: Simulation,
+	max_steps: usize,
+	max_states: Option<usize>,
+}
+
+/// A rotation found by [`OptimalSolver`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct OptimalSolution {
+	pub rotation: Vec<CraftingActionEnum>,
+	/// Whether the search finished, so that no rotation of at most `max_steps` steps
+	/// reaches more quality; `false` if it was stopped at `max_states`.
+	pub optimal: bool,
+}
+
+#[bon]
+impl OptimalSolver {
+	/// * `max_states`: how many states to search at most, unlimited by default
+	#[builder]
+	pub fn new(
+		recipe: Craft,
+		crafter_stats: CrafterStats,
+		hq_ingredients: Option<Vec<Ingredient>>,
+		max_steps: Option<usize>,
+		max_states: Option<usize>,
+	) -> Self {
+		let simulation = Simulation::builder()
+			.recipe(recipe)
+			.crafter_stats(crafter_stats)
+			.maybe_hq_ingredients(hq_ingredients)
+			.build();
+
+		Self {
+			simulation,
+			max_steps: max_steps.unwrap_or(50),
+			max_states,
+		}
+	}
+
+	/// Returns the best rotation found, or `None` if the craft can't be completed.
+	pub fn solve(&self) -> Option<Vec<CraftingActionEnum>> {
+		self.solution().map(|solution| solution.rotation)
+	}
+
+	/// Returns the best rotation found and whether it is proven the best, or `None` if
+	/// the craft can't be completed.
+	pub fn solution(&self) -> Option<OptimalSolution> {
+		// only rotations at least as good as a wide beam search's are worth searching for
+		let mut floor = 0;
+		let beam = BeamSearchSolver::from_simulation(self.simulation.clone(), 1024, self.max_steps)
+			.solve();
+		if let Some(rotation) = &beam {
+			let mut sim = self.simulation.clone();
+			for action in rotation {
+				apply_action(&mut sim, action);
+			}
+			floor = sim.quality.min(sim.recipe.quality);
+		}
+
+		let mut memo = HashMap::new();
+		let mut bound = QualityBound::new(&self.simulation);
+		let mut states_left = self.max_states.unwrap_or(usize::MAX);
+		let found = self
+			.search(
+				&self.simulation,
+				floor,
+				&mut memo,
+				&mut bound,
+				&mut states_left,
+			)
+			.0;
+		// running out of states exactly at the end still counts as stopped
+		let optimal = states_left > 0;
+		if found.is_none() {
+			return beam.map(|rotation| OptimalSolution { rotation, optimal });
+		}
+
+		let mut sim = self.simulation.clone();
+		let mut rotation = vec![];
+		while sim.success.is_none() {
+			let best = memo.get(&StateKey::from(&sim))?.best.as_ref()?;
+			apply_action(&mut sim, &best.action);
+			rotation.push(best.action);
+		}
+		Some(OptimalSolution { rotation, optimal })
+	}
+
+	/// The quality and length of the best rotation from `sim` if its quality reaches
+	/// `floor`, and whether running out of steps (or of states to search) cut the search
+	/// short.
+	fn search(
+		&self,
+		sim: &Simulation,
+		floor: u32,
+		memo: &mut HashMap<StateKey, Searched>,
+		bound: &mut QualityBound,
+		states_left: &mut usize,
+	) -> (Option<(u32, usize)>, bool) {
+		let steps_left = self.max_steps.saturating_sub(sim.steps.len());
+		match sim.success {
+			Some(true) => {
+				let quality = sim.quality.min(sim.recipe.quality);
+				return ((quality >= floor).then_some((quality, 0)), false);
+			}
+			Some(false) => return (None, false),
+			None if steps_left == 0 => return (None, true),
+			None => (),
+		}
+
+		let key = StateKey::from(sim);
+		if let Some(searched) = memo.get(&key) {
+			if let Some(best) = searched.reuse(floor, steps_left) {
+				return (best, searched.cut_off);
+			}
+		}
+		if *states_left == 0 {
+			return (None, true);
+		}
+		*states_left -= 1;
+		let mut children: Vec<_> = guaranteed_actions(sim)
+			.into_iter()
+			.map(|action| {
+				let mut next = sim.clone();
+				apply_action(&mut next, &action);
+				(bound.quality(&next), action, next)
+			})
+			.collect();
+		children.sort_by_key(|(quality, ..)| Reverse(*quality));
+
+		let mut best: Option<BestStep> = None;
+		let mut cut_off = false;
+		for (upper_bound, action, next) in children {
+			let needed = best.as_ref().map_or(floor, |best| best.quality.max(floor));
+			// the children left can only be worse
+			if upper_bound < needed {
+				break;
+			}
+			let (found, child_cut_off) = self.search(&next, needed, memo, bound, states_left);
+			cut_off |= child_cut_off;
+			if let Some((quality, steps)) = found {
+				let steps = steps + 1;
+				// prefer higher quality, then shorter rotations
+				if best.as_ref().map_or(true, |b| {
+					quality > b.quality || (quality == b.quality && steps < b.steps)
+				}) {
+					best = Some(BestStep {
+						action,
+						quality,
+						steps,
+					});
+				}
+			}
+		}
+
+		let result = best.as_ref().map(|best| (best.quality, best.steps));
+		// an exact result is worth more than knowing that the quality is too low
+		if best.is_some() || memo.get(&key).map_or(true, |old| old.best.is_none()) {
+			memo.insert(
+				key,
+				Searched {
+					best,
+					floor,
+					steps_left,
+					cut_off,
+				},
+			);
+		}
+		(result, cut_off)
+	}
+}
+
+struct BestStep {
+	action: CraftingActionEnum,
+	quality: u32,
+	steps: usize,
+}
+
+/// What searching a state found: its best step if the best quality reached `floor`,
+/// nothing if it didn't or if the craft can't be completed from there.
+struct Searched {
+	best: Option<BestStep>,
+	floor: u32,
+	steps_left: usize,
+	/// Whether running out of steps kept some rotations from finishing.
+	cut_off: bool,
+}
+
+impl Searched {
+	/// The quality and length of the best rotation for the same state reached with
+	/// `steps_left` steps left, if this search still tells it for `floor`.
+	fn reuse(&self, floor: u32, steps_left: usize) -> Option<Option<(u32, usize)>> {
+		// fewer steps can't do better, and more don't matter if none ran out
+		if steps_left > self.steps_left && self.cut_off {
+			return None;
+		}
+		match &self.best {
+			Some(best) if best.steps <= steps_left => {
+				Some((best.quality >= floor).then_some((best.quality, best.steps)))
+			}
+			Some(_) => None,
+			None if floor >= self.floor => Some(None),
+			None => None,
+		}
+	}
+}
+
+/// An upper bound on the quality a craft in progress can still reach.
+///
+/// It relaxes the craft into spending its CP and durability on touches, with CP turned
+/// into durability at the cheapest rate any action restores it, and what the active
+/// buffs have already been paid for added back. Touches always get their combo,
+/// Innovation, Great Strides and Waste Not are paid for per touch, and the progress left
+/// is paid for at the cheapest rate any synthesis gets, so no rotation can do better
+/// than the best way of spending what is left.
+struct QualityBound {
+	recipe_quality: u32,
+	/// The quality of a 100 potency touch and the progress of a 100 potency synthesis,
+	/// with no bonus.
+	base_quality: f64,
+	base_progress: f64,
+	/// CP per point of durability.
+	durability_price: f64,
+	/// CP per step of Waste Not, if known.
+	waste_not: Option<f64>,
+	/// The most durability a step costs.
+	step_durability: u32,
+	trained_perfection: bool,
+	/// The least CP (with durability at its price) a point of synthesis potency costs.
+	potency_price: f64,
+	/// The most potency a synthesis has.
+	synthesis_potency: f64,
+	/// Every touch the crafter knows, with and without Waste Not.
+	touches: Vec<Touch>,
+	/// (CP, quality bonus) of each way of buffing a touch, including not buffing it.
+	buffs: Vec<(f64, f64)>,
+	/// The most durability, in units of 5, the table goes up to.
+	max_durability: usize,
+	/// The most potency (with Inner Quiet and buffs) touches can add, by CP, durability
+	/// and Inner Quiet stacks; grown with CP as needed.
+	table: Vec<Vec<[f64; 11]>>,
+}
+
+struct Touch {
+	cp: f64,
+	/// In units of 5, rounded down.
+	durability: usize,
+	potency: u32,
+	stacks: Stacks,
+}
+
+#[derive(Clone, Copy)]
+enum Stacks {
+	Gained(u32),
+	/// Byregot's Blessing, whose potency grows with the stacks it uses up.
+	Spent,
+	/// Trained Finesse, which needs every stack.
+	Needed,
+}
+
+impl QualityBound {
+	fn new(sim: &Simulation) -> Self {
+		let level = sim.crafter_stats.level;
+
+		// (level, CP, durability restored): Master's Mend, Manipulation, Immaculate Mend
+		let durability_price = [
+			(7, 88.0, 30.0),
+			(65, 96.0, 40.0),
+			(98, 112.0, sim.recipe.durability as f64),
+		]
+		.into_iter()
+		.filter(|&(required, _, durability)| level >= required && durability > 0.0)
+		.map(|(_, cp, durability)| cp / durability)
+		.fold(88.0 / 30.0, f64::min);
+		let waste_not = if level >= 47 {
+			Some(98.0 / 8.0)
+		} else if level >= 15 {
+			Some(56.0 / 4.0)
+		} else {
+			None
+		};
+		// (CP, durability) of a step, with and without Waste Not
+		let with_waste_not = |cp: f64, durability: u32| {
+			let mut costs = vec![(cp, durability)];
+			if let Some(waste_not) = waste_not {
+				costs.push((cp + waste_not, (durability + 1) / 2));
+			}
+			costs
+		};
+
+		// (level, CP, durability, potency)
+		let syntheses: Vec<_> = [
+			(1, 0, 10, if level >= 31 { 120 } else { 100 }),
+			(62, 7, 10, if level >= 82 { 180 } else { 150 }),
+			(72, 18, 20, if level >= 86 { 360 } else { 300 }),
+			(88, 18, 5, 180),
+		]
+		.into_iter()
+		.filter(|&(required, ..)| level >= required)
+		.collect();
+		// Veneration's CP spread over its four steps, like Innovation's below
+		let venerations: &[(f64, f64)] = if level >= 15 {
+			&[(0.0, 0.0), (18.0 / 4.0, 0.5)]
+		} else {
+			&[(0.0, 0.0)]
+		};
+		let mut potency_price = f64::INFINITY;
+		for &(_, cp, durability, potency) in &syntheses {
+			for (cp, durability) in with_waste_not(cp as f64, durability) {
+				for &(veneration, bonus) in venerations {
+					let price = (cp + veneration + durability as f64 * durability_price)
+						/ (potency as f64 * (1.0 + bonus));
+					potency_price = potency_price.min(price);
+				}
+			}
+		}
+
+		// (level, CP, durability, potency, stacks, synthesis potency): combo touches at
+		// their combo cost, and Precise Touch as if Heart and Soul were always up
+		let known: Vec<_> = [
+			(5, 18, 10, 100, Stacks::Gained(1), 0),
+			(18, 18, 10, 125, Stacks::Gained(1), 0),
+			(84, 18, 10, 150, Stacks::Gained(1), 0),
+			(66, 25, 5, 100, Stacks::Gained(1), 0),
+			(71, 40, 20, 200, Stacks::Gained(2), 0),
+			(76, 32, 10, 100, Stacks::Gained(1), 100),
+			(86, 18, 10, 150, Stacks::Gained(2), 0),
+			(92, 24, 10, 100, Stacks::Gained(2), 0),
+			(50, 24, 10, 100, Stacks::Spent, 0),
+			(90, 32, 0, 100, Stacks::Needed, 0),
+		]
+		.into_iter()
+		.filter(|&(required, ..)| level >= required)
+		.collect();
+		let mut touches = vec![];
+		for &(_, cp, durability, potency, stacks, synthesis) in &known {
+			// no Inner Quiet before its trait
+			let stacks = match stacks {
+				Stacks::Gained(_) if level < 11 => Stacks::Gained(0),
+				stacks => stacks,
+			};
+			// Delicate Synthesis's progress comes off its CP, since the progress left is
+			// paid for separately
+			let cp = cp as f64 - synthesis as f64 * 1.5 * potency_price;
+			for (cp, durability) in with_waste_not(cp, durability) {
+				touches.push(Touch {
+					cp: cp.max(1.0),
+					durability: durability as usize / 5,
+					potency,
+					stacks,
+				});
+			}
+		}
+
+		let mut buffs = vec![(0.0, 0.0)];
+		if level >= 21 {
+			buffs.push((32.0, 1.0));
+		}
+		if level >= 26 {
+			buffs.push((18.0 / 4.0, 0.5));
+			buffs.push((18.0 / 4.0 + 32.0, 1.5));
+		}
+
+		let step_durability = known
+			.iter()
+			.map(|&(_, _, durability, ..)| durability)
+			.chain(syntheses.iter().map(|&(_, _, durability, _)| durability))
+			.max()
+			.unwrap_or(0);
+		Self {
+			recipe_quality: sim.recipe.quality,
+			base_quality: actions::BasicTouch.get_base_quality(sim) as f64,
+			base_progress: actions::BasicSynthesis.get_base_progression(sim) as f64,
+			durability_price,
+			waste_not,
+			step_durability,
+			trained_perfection: level >= 100,
+			potency_price,
+			synthesis_potency: syntheses
+				.iter()
+				.map(|&(.., potency)| potency as f64)
+				.fold(0.0, f64::max),
+			touches,
+			buffs,
+			// Manipulation and Trained Perfection can add to a full durability
+			max_durability: (sim.recipe.durability + 40 + step_durability) as usize / 5 + 1,
+			table: vec![],
+		}
+	}
+
+	fn quality(&mut self, sim: &Simulation) -> u32 {
+		if sim.success.is_some() {
+			return sim.quality.min(self.recipe_quality);
+		}
+		// openers and Trained Eye aren't worth modelling
+		if sim.steps.iter().all(|step| step.action.skips_buff_ticks()) {
+			return self.recipe_quality;
+		}
+
+		let duration = |buff| sim.get_buff(buff).map_or(0.0, |b| b.duration as f64);
+		let mut cp = sim.available_cp as f64;
+		cp += (duration(Buff::Innovation) + duration(Buff::Veneration)) * 18.0 / 4.0;
+		if sim.has_buff(Buff::GreatStrides) {
+			cp += 32.0;
+		}
+		if let Some(waste_not) = self.waste_not {
+			cp += duration(Buff::WasteNot).max(duration(Buff::WasteNotII)) * waste_not;
+		}
+		let mut durability = sim.durability.max(0) as f64 + duration(Buff::Manipulation) * 5.0;
+		if self.trained_perfection
+			&& (sim.has_buff(Buff::TrainedPerfection)
+				|| !sim
+					.steps
+					.iter()
+					.any(|step| step.action == actions::TrainedPerfection.into()))
+		{
+			durability += self.step_durability as f64;
+		}
+
+		// the progress left is best paid for with durability first, since CP can
+		// always be turned into durability but not the other way around
+		let progress = sim.recipe.progress.saturating_sub(sim.progression) as f64;
+		let mut potency = progress * 100.0 / self.base_progress.max(1.0);
+		if sim.has_buff(Buff::MuscleMemory) {
+			potency -= self.synthesis_potency;
+		}
+		let progress_price = potency.max(0.0) * self.potency_price;
+		let paid = durability.min(progress_price / self.durability_price);
+		durability -= paid;
+		cp -= progress_price - paid * self.durability_price;
+		if cp < 0.0 {
+			return 0;
+		}
+
+		let stacks = sim
+			.get_buff(Buff::InnerQuiet)
+			.map_or(0, |b| b.stacks.min(10)) as usize;
+		let durability = ((durability / 5.0).ceil() as usize).min(self.max_durability);
+		let potency = self.potency(cp.ceil() as usize, durability, stacks);
+		let quality = sim.quality as f64 + potency * self.base_quality / 100.0;
+		(quality.ceil() as u32).min(self.recipe_quality)
+	}
+
+	fn potency(&mut self, cp: usize, durability: usize, stacks: usize) -> f64 {
+		// durability units bought with CP
+		let unit_price = 5.0 * self.durability_price;
+		while self.table.len() <= cp {
+			let cp = self.table.len();
+			let mut row = vec![[0.0; 11]; self.max_durability + 1];
+			for (durability, row) in row.iter_mut().enumerate() {
+				for (stacks, best) in row.iter_mut().enumerate() {
+					for touch in &self.touches {
+						let (potency, next_stacks) = match touch.stacks {
+							Stacks::Gained(gained) => (touch.potency, stacks + gained as usize),
+							Stacks::Spent if stacks > 0 => ((100 + 20 * stacks as u32).min(300), 0),
+							Stacks::Needed if stacks == 10 => (touch.potency, stacks),
+							_ => continue,
+						};
+						let potency = potency as f64 * (1.0 + stacks as f64 / 10.0);
+						let missing = touch.durability.saturating_sub(durability);
+						let next_durability = durability.saturating_sub(touch.durability);
+						for &(buff_cp, bonus) in &self.buffs {
+							let cost = touch.cp + buff_cp + missing as f64 * unit_price;
+							let cost = (cost.floor() as usize).max(1);
+							if cost <= cp {
+								let total = potency * (1.0 + bonus)
+									+ self.table[cp - cost][next_durability][next_stacks.min(10)];
+								*best = f64::max(*best, total);
+							}
+						}
+					}
+				}
+			}
+			self.table.push(row);
+		}
+		self.table[cp][durability][stacks]
+	}
+}
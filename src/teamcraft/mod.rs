@@ -0,0 +1,2 @@
+mod rotation;
+pub use rotation::*;
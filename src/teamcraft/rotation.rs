@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+
+use crate::types::enums::CraftingActionEnum;
+
+/// A rotation entry that doesn't match any current action, usually one removed
+/// from the game since the rotation was saved (`InnerQuiet`, `FocusedTouch`, ...).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnknownRotationAction {
+	/// 0-based position in the rotation.
+	pub index: usize,
+	pub name: String,
+}
+
+#[derive(Debug)]
+pub enum TeamcraftImportError {
+	Json(serde_json::Error),
+	UnknownActions(Vec<UnknownRotationAction>),
+}
+
+impl fmt::Display for TeamcraftImportError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			TeamcraftImportError::Json(err) => write!(f, "invalid teamcraft JSON: {}", err),
+			TeamcraftImportError::UnknownActions(unknown_actions) => {
+				let messages: Vec<_> = unknown_actions
+					.iter()
+					.map(|unknown| {
+						format!("step {}: unknown action \"{}\"", unknown.index + 1, unknown.name)
+					})
+					.collect();
+				write!(f, "{}", messages.join("; "))
+			}
+		}
+	}
+}
+
+impl std::error::Error for TeamcraftImportError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			TeamcraftImportError::Json(err) => Some(err),
+			TeamcraftImportError::UnknownActions(_) => None,
+		}
+	}
+}
+
+impl From<serde_json::Error> for TeamcraftImportError {
+	fn from(err: serde_json::Error) -> Self {
+		TeamcraftImportError::Json(err)
+	}
+}
+
+/// A rotation as saved by teamcraft.
+///
+/// Only the name and the actions are interpreted; every other field (recipe, stats,
+/// folder, ...) is kept as-is in `extra` so a round trip doesn't lose anything.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TeamcraftSavedRotation {
+	#[serde(rename = "$key", default, skip_serializing_if = "Option::is_none")]
+	pub key: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+	/// Action registry names, exactly as stored by teamcraft.
+	pub rotation: Vec<String>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
+}
+
+impl TeamcraftSavedRotation {
+	pub fn new(name: Option<String>, rotation: &[CraftingActionEnum]) -> Self {
+		Self {
+			name,
+			rotation: rotation.iter().map(|action| action.id().to_string()).collect(),
+			..Default::default()
+		}
+	}
+
+	/// Maps the saved registry names onto actions, listing every name that no
+	/// longer exists.
+	pub fn actions(&self) -> Result<Vec<CraftingActionEnum>, TeamcraftImportError> {
+		actions_from_ids(&self.rotation)
+	}
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RotationJson {
+	Actions(Vec<String>),
+	Saved(TeamcraftSavedRotation),
+}
+
+/// Reads a rotation from teamcraft JSON, either a plain array of action registry
+/// names (`["MuscleMemory", "Manipulation", ...]`) or a saved rotation object.
+pub fn import_rotation(json: &str) -> Result<Vec<CraftingActionEnum>, TeamcraftImportError> {
+	match serde_json::from_str(json)? {
+		RotationJson::Actions(ids) => actions_from_ids(&ids),
+		RotationJson::Saved(saved) => saved.actions(),
+	}
+}
+
+/// Reads one saved rotation, or an array of them, from teamcraft JSON.
+///
+/// Action names aren't checked here; see [`TeamcraftSavedRotation::actions`].
+pub fn import_saved_rotations(
+	json: &str,
+) -> Result<Vec<TeamcraftSavedRotation>, TeamcraftImportError> {
+	#[derive(Deserialize)]
+	#[serde(untagged)]
+	enum SavedRotationsJson {
+		Many(Vec<TeamcraftSavedRotation>),
+		One(TeamcraftSavedRotation),
+	}
+
+	Ok(match serde_json::from_str(json)? {
+		SavedRotationsJson::Many(saved) => saved,
+		SavedRotationsJson::One(saved) => vec![saved],
+	})
+}
+
+/// Writes a rotation as a teamcraft JSON array of action registry names,
+/// ready to paste into teamcraft's rotation import.
+pub fn export_rotation(rotation: &[CraftingActionEnum]) -> String {
+	let ids: Vec<_> = rotation.iter().map(|action| action.id()).collect();
+	serde_json::to_string(&ids).unwrap()
+}
+
+fn actions_from_ids(ids: &[String]) -> Result<Vec<CraftingActionEnum>, TeamcraftImportError> {
+	let mut rotation = vec![];
+	let mut unknown_actions = vec![];
+
+	for (index, id) in ids.iter().enumerate() {
+		match CraftingActionEnum::from_id(id) {
+			Some(action) => rotation.push(action),
+			None => unknown_actions.push(UnknownRotationAction {
+				index,
+				name: id.clone(),
+			}),
+		}
+	}
+
+	if unknown_actions.is_empty() {
+		Ok(rotation)
+	} else {
+		Err(TeamcraftImportError::UnknownActions(unknown_actions))
+	}
+}
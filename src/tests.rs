@@ -1,11 +1,22 @@
 use anyhow::Result;
-use std::collections::{HashMap, HashSet};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
 
+use crate::evaluation::{ExactEvaluator, MonteCarlo};
+use crate::game_macro::{import_macro, is_action_command, MacroExporter, UnknownAction};
+use crate::recipes::{CraftingList, RecipeDataError, RecipeDatabase};
+use crate::solver::{
+	BeamSearchSolver, GeneticSolver, HqIngredientPlan, HqIngredientSolver, MctsPlanner,
+	OptimalSolver,
+};
 use crate::types::{
 	actions,
-	enums::{Buff, CraftingActionEnum, StepState},
-	structs::{Craft, CrafterLevels, CrafterStats, CraftingLevel},
-	tables,
+	enums::{Buff, CraftingActionEnum, CraftingJob, FailCause, StepState},
+	structs::{Craft, CrafterLevels, CrafterStats, CraftingLevel, SimulationResult},
+	tables::{self, RecipeLevel, RecipeLevelError, RecipeLevelMismatch, RecipeLevelTable},
 	traits::CraftingAction,
 	Simulation,
 };
@@ -1233,6 +1244,2355 @@ fn test_groundwork_efficiency_with_trained_perfection() -> Result<()> {
 	Ok(())
 }
 
+#[test]
+fn test_seeded_simulation_is_reproducible() -> Result<()> {
+	let recipe = generate_recipe_rlvl(3864, 80, 480, 80, 6178, 36208, 110, 90);
+	let stats = generate_stats(80, 2745, 2885, 626);
+	let actions: Vec<CraftingActionEnum> = vec![
+		actions::HastyTouch.into(),
+		actions::HastyTouch.into(),
+		actions::HastyTouch.into(),
+		actions::HastyTouch.into(),
+		actions::RapidSynthesis.into(),
+		actions::RapidSynthesis.into(),
+		actions::RapidSynthesis.into(),
+	];
+	let run = |seed: u64| {
+		Simulation::builder()
+			.recipe(recipe.clone())
+			.crafter_stats(stats.clone())
+			.actions(actions.clone())
+			.seed(seed)
+			.build()
+			.start()
+			.run()
+	};
+
+	let first = run(1234);
+	let second = run(1234);
+	assert_eq!(first.simulation.quality, second.simulation.quality);
+	assert_eq!(first.simulation.progression, second.simulation.progression);
+	assert!(first
+		.steps
+		.iter()
+		.zip(second.steps.iter())
+		.all(|(a, b)| a.success == b.success && a.state == b.state));
+
+	Ok(())
+}
+
+#[test]
+fn test_condition_stream_independent_of_actions() -> Result<()> {
+	let recipe = Craft {
+		durability: 200,
+		..generate_recipe_rlvl(3864, 80, 480, 80, 6178, 36208, 110, 90)
+	};
+	let stats = generate_stats(80, 2745, 2885, 626);
+	let run = |first_action: CraftingActionEnum, seed: u64| {
+		let mut actions = vec![first_action];
+		actions.extend(std::iter::repeat(CraftingActionEnum::from(actions::HastyTouch)).take(12));
+		Simulation::builder()
+			.recipe(recipe.clone())
+			.crafter_stats(stats.clone())
+			.actions(actions)
+			.seed(seed)
+			.build()
+			.start()
+			.run()
+	};
+
+	let observed = run(actions::Observe.into(), 99);
+	let touched = run(actions::HastyTouch.into(), 99);
+	let states = |result: &SimulationResult| {
+		result.steps.iter().map(|step| step.state).collect::<Vec<_>>()
+	};
+	assert_eq!(states(&observed), states(&touched));
+	// the conditions really do evolve, and with the seed
+	assert!(states(&observed)
+		.iter()
+		.any(|&state| state != StepState::Normal));
+	assert_ne!(states(&observed), states(&run(actions::Observe.into(), 100)));
+
+	Ok(())
+}
+
+#[test]
+fn test_injected_condition_rng() -> Result<()> {
+	let recipe = generate_recipe_rlvl(3864, 80, 480, 80, 6178, 36208, 110, 90);
+	let stats = generate_stats(80, 2745, 2885, 626);
+	let tick_states = || {
+		let mut sim = Simulation::builder()
+			.recipe(recipe.clone())
+			.crafter_stats(stats.clone())
+			.condition_rng(Box::new(StdRng::seed_from_u64(7)))
+			.build();
+		(0..32)
+			.map(|_| {
+				sim.tick_state();
+				sim.state()
+			})
+			.collect::<Vec<_>>()
+	};
+
+	assert_eq!(tick_states(), tick_states());
+
+	Ok(())
+}
+
+#[test]
+fn test_monte_carlo_statistics() -> Result<()> {
+	let recipe = generate_recipe_rlvl(3864, 80, 480, 80, 6178, 36208, 110, 90);
+	let stats = generate_stats(80, 2745, 2885, 626);
+	let actions: Vec<CraftingActionEnum> = vec![
+		actions::HastyTouch.into(),
+		actions::HastyTouch.into(),
+		actions::RapidSynthesis.into(),
+		actions::RapidSynthesis.into(),
+		actions::RapidSynthesis.into(),
+		actions::RapidSynthesis.into(),
+		actions::RapidSynthesis.into(),
+		actions::RapidSynthesis.into(),
+		actions::RapidSynthesis.into(),
+		actions::RapidSynthesis.into(),
+	];
+	let evaluate = |threads: usize| {
+		MonteCarlo::builder()
+			.recipe(recipe.clone())
+			.crafter_stats(stats.clone())
+			.actions(actions.clone())
+			.runs(500)
+			.threads(threads)
+			.seed(42)
+			.build()
+			.run()
+	};
+
+	let single = evaluate(1);
+	let threaded = evaluate(4);
+	assert_eq!(single.runs, 500);
+	assert_eq!(single.successes, threaded.successes);
+	assert_eq!(single.qualities, threaded.qualities);
+	assert!(single.success_rate() > 0.0 && single.success_rate() < 1.0);
+	assert_eq!(
+		single.hq_percent_distribution.values().sum::<usize>(),
+		single.runs
+	);
+	assert!(single.quality_percentile(10.0) <= single.quality_percentile(90.0));
+	assert!(single.fail_cause_rate(FailCause::DurabilityReachedZero) > 0.0);
+
+	Ok(())
+}
+
+#[test]
+fn test_exact_evaluation_success_roll() -> Result<()> {
+	let recipe = generate_recipe_rlvl(3864, 80, 480, 80, 6178, 36208, 110, 90);
+	let stats = generate_stats(80, 2745, 2885, 626);
+	let result = ExactEvaluator::builder()
+		.recipe(recipe)
+		.crafter_stats(stats)
+		.actions(vec![actions::HastyTouch.into()])
+		.build()
+		.run();
+
+	let total: f64 = result.outcomes.iter().map(|outcome| outcome.probability).sum();
+	assert!((total - 1.0).abs() < 1e-9);
+	// the first step is always Normal, so only the success roll matters
+	assert_eq!(result.outcomes.len(), 2);
+	assert!((result.quality_distribution()[&0] - 0.39).abs() < 1e-9);
+
+	Ok(())
+}
+
+#[test]
+fn test_exact_evaluation_matches_monte_carlo() -> Result<()> {
+	let recipe = generate_recipe_rlvl(3864, 80, 480, 80, 6178, 36208, 110, 90);
+	let stats = generate_stats(80, 2745, 2885, 626);
+	let actions: Vec<CraftingActionEnum> = vec![
+		actions::Observe.into(),
+		actions::BasicTouch.into(),
+		actions::HastyTouch.into(),
+		actions::BasicTouch.into(),
+		actions::RapidSynthesis.into(),
+		actions::RapidSynthesis.into(),
+		actions::RapidSynthesis.into(),
+	];
+
+	let exact = ExactEvaluator::builder()
+		.recipe(recipe.clone())
+		.crafter_stats(stats.clone())
+		.actions(actions.clone())
+		.build()
+		.run();
+	let sampled = MonteCarlo::builder()
+		.recipe(recipe)
+		.crafter_stats(stats)
+		.actions(actions)
+		.runs(20_000)
+		.threads(4)
+		.seed(3)
+		.build()
+		.run();
+
+	let expected_quality = exact.expected_quality();
+	assert!((sampled.mean_quality() - expected_quality).abs() / expected_quality < 0.02);
+	assert!((sampled.success_rate() - exact.success_probability()).abs() < 0.02);
+	assert!(
+		(sampled.fail_cause_rate(FailCause::DurabilityReachedZero)
+			- exact.fail_cause_probability(FailCause::DurabilityReachedZero))
+		.abs() < 0.02
+	);
+
+	Ok(())
+}
+
+#[test]
+fn test_exact_evaluation_merges_branches() -> Result<()> {
+	// with the Normal condition only, a branch is down to how many touches succeeded
+	// and whether the last one did
+	let recipe = Craft {
+		durability: 300,
+		conditions_flag: 1,
+		..generate_recipe_rlvl(3864, 80, 480, 80, 6178, 36208, 110, 90)
+	};
+	let stats = generate_stats(80, 2745, 2885, 626);
+	let result = ExactEvaluator::builder()
+		.recipe(recipe)
+		.crafter_stats(stats)
+		.actions(vec![actions::HastyTouch.into(); 24])
+		.build()
+		.run();
+
+	let total: f64 = result.outcomes.iter().map(|outcome| outcome.probability).sum();
+	assert!((total - 1.0).abs() < 1e-9);
+	// following every order of successes would take 2^24 branches
+	assert!(
+		result.peak_branches <= 2 * 25,
+		"{} branches",
+		result.peak_branches
+	);
+
+	Ok(())
+}
+
+#[test]
+fn test_optimal_solver_real_recipe() -> Result<()> {
+	let recipe = generate_recipe_rlvl(3864, 80, 480, 80, 6178, 36208, 110, 90);
+	let stats = generate_stats(80, 2745, 2885, 626);
+	// too many states to prove the best rotation, but the search still improves on a beam
+	let solution = OptimalSolver::builder()
+		.recipe(recipe.clone())
+		.crafter_stats(stats.clone())
+		.max_states(100_000)
+		.build()
+		.solution()
+		.unwrap();
+
+	let quality = |actions: Vec<CraftingActionEnum>| {
+		let result = Simulation::builder()
+			.recipe(recipe.clone())
+			.crafter_stats(stats.clone())
+			.actions(actions)
+			.build()
+			.start()
+			.linear(true)
+			.run();
+		assert!(result.success);
+		result.simulation.quality
+	};
+	let beam = BeamSearchSolver::builder()
+		.recipe(recipe.clone())
+		.crafter_stats(stats.clone())
+		.build()
+		.solve()
+		.unwrap();
+	assert!(quality(solution.rotation) >= quality(beam));
+
+	Ok(())
+}
+
+#[test]
+fn test_optimal_solver_matches_exhaustive_search() -> Result<()> {
+	use crate::solver::{apply_action, guaranteed_actions};
+
+	fn best_quality(sim: &Simulation, steps_left: usize) -> Option<u32> {
+		match sim.success {
+			Some(true) => return Some(sim.quality.min(sim.recipe.quality)),
+			Some(false) => return None,
+			None if steps_left == 0 => return None,
+			None => (),
+		}
+		guaranteed_actions(sim)
+			.into_iter()
+			.filter_map(|action| {
+				let mut next = sim.clone();
+				apply_action(&mut next, &action);
+				best_quality(&next, steps_left - 1)
+			})
+			.max()
+	}
+
+	let recipe = generate_recipe_lvl(3864, 15, 40, 150, 1000, 50, 30);
+	let stats = generate_stats(15, 250, 100, 100);
+	let solution = OptimalSolver::builder()
+		.recipe(recipe.clone())
+		.crafter_stats(stats.clone())
+		.max_steps(7)
+		.build()
+		.solution()
+		.unwrap();
+	assert!(solution.optimal);
+	assert!(solution.rotation.len() <= 7);
+
+	let sim = Simulation::builder()
+		.recipe(recipe)
+		.crafter_stats(stats)
+		.build();
+	let mut solved = sim.clone();
+	for action in &solution.rotation {
+		apply_action(&mut solved, action);
+	}
+	assert_eq!(solved.success, Some(true));
+	assert_eq!(Some(solved.quality.min(solved.recipe.quality)), best_quality(&sim, 7));
+
+	Ok(())
+}
+
+#[test]
+fn test_optimal_solver() -> Result<()> {
+	let recipe = Craft {
+		durability: 40,
+		..generate_recipe_lvl(3864, 15, 40, 100, 1000, 50, 30)
+	};
+	let stats = generate_stats(15, 250, 100, 150);
+	let rotation = OptimalSolver::builder()
+		.recipe(recipe.clone())
+		.crafter_stats(stats.clone())
+		.build()
+		.solve()
+		.unwrap();
+
+	let result = Simulation::builder()
+		.recipe(recipe.clone())
+		.crafter_stats(stats.clone())
+		.actions(rotation)
+		.build()
+		.start()
+		.linear(true)
+		.run();
+	assert!(result.success);
+
+	let hand_written = Simulation::builder()
+		.recipe(recipe)
+		.crafter_stats(stats)
+		.actions(vec![
+			actions::BasicSynthesis.into(),
+			actions::BasicTouch.into(),
+			actions::BasicTouch.into(),
+			actions::BasicSynthesis.into(),
+		])
+		.build()
+		.start()
+		.linear(true)
+		.run();
+	assert!(hand_written.success);
+	assert!(result.simulation.quality > hand_written.simulation.quality);
+
+	Ok(())
+}
+
+#[test]
+fn test_beam_search_solver() -> Result<()> {
+	let recipe = Craft {
+		durability: 40,
+		..generate_recipe_lvl(3864, 15, 40, 100, 1000, 50, 30)
+	};
+	let stats = generate_stats(15, 250, 100, 150);
+	let run = |rotation: Vec<CraftingActionEnum>| {
+		Simulation::builder()
+			.recipe(recipe.clone())
+			.crafter_stats(stats.clone())
+			.actions(rotation)
+			.build()
+			.start()
+			.linear(true)
+			.run()
+	};
+
+	let optimal = OptimalSolver::builder()
+		.recipe(recipe.clone())
+		.crafter_stats(stats.clone())
+		.build()
+		.solve()
+		.unwrap();
+	let beam = BeamSearchSolver::builder()
+		.recipe(recipe.clone())
+		.crafter_stats(stats.clone())
+		.width(32)
+		.build()
+		.solve()
+		.unwrap();
+	let optimal = run(optimal);
+	let beam = run(beam);
+	assert!(beam.success);
+	assert_eq!(beam.simulation.quality, optimal.simulation.quality);
+
+	// a narrow beam that only looks at progress still completes the craft
+	let rushed = BeamSearchSolver::builder()
+		.recipe(recipe.clone())
+		.crafter_stats(stats.clone())
+		.width(1)
+		.score(Arc::new(|sim: &Simulation| sim.progression as f64))
+		.build()
+		.solve()
+		.unwrap();
+	let rushed = run(rushed);
+	assert!(rushed.success);
+	assert!(rushed.simulation.quality <= optimal.simulation.quality);
+
+	// a score can aim for the caller's own target
+	let target = optimal.simulation.quality / 2;
+	let targeted = BeamSearchSolver::builder()
+		.recipe(recipe.clone())
+		.crafter_stats(stats.clone())
+		.width(8)
+		.score(Arc::new(move |sim: &Simulation| {
+			(sim.quality.min(target) * 10 + sim.progression) as f64
+		}))
+		.build()
+		.solve()
+		.unwrap();
+	let targeted = run(targeted);
+	assert!(targeted.success);
+	assert!(targeted.simulation.quality >= target);
+
+	// narrow beams of quality lines still keep one that can finish a real recipe
+	let recipe = generate_recipe_rlvl(3864, 80, 480, 80, 6178, 36208, 110, 90);
+	let stats = generate_stats(80, 2745, 2885, 626);
+	for width in 1..=8 {
+		let rotation = BeamSearchSolver::builder()
+			.recipe(recipe.clone())
+			.crafter_stats(stats.clone())
+			.width(width)
+			.build()
+			.solve()
+			.unwrap();
+		let result = Simulation::builder()
+			.recipe(recipe.clone())
+			.crafter_stats(stats.clone())
+			.actions(rotation)
+			.build()
+			.start()
+			.linear(true)
+			.run();
+		assert!(result.success);
+	}
+
+	Ok(())
+}
+
+#[test]
+fn test_genetic_solver() -> Result<()> {
+	let recipe = Craft {
+		durability: 40,
+		..generate_recipe_lvl(3864, 15, 40, 100, 1000, 50, 30)
+	};
+	let stats = generate_stats(15, 250, 100, 150);
+	let initial_rotation: Vec<CraftingActionEnum> = vec![
+		actions::BasicSynthesis.into(),
+		actions::BasicTouch.into(),
+		actions::BasicSynthesis.into(),
+	];
+	let run = |rotation: Vec<CraftingActionEnum>| {
+		Simulation::builder()
+			.recipe(recipe.clone())
+			.crafter_stats(stats.clone())
+			.actions(rotation)
+			.build()
+			.start()
+			.linear(true)
+			.run()
+	};
+	let solve = || {
+		GeneticSolver::builder()
+			.recipe(recipe.clone())
+			.crafter_stats(stats.clone())
+			.initial_rotation(initial_rotation.clone())
+			.population_size(40)
+			.generations(40)
+			.max_length(15)
+			.seed(11)
+			.build()
+			.solve()
+			.unwrap()
+	};
+
+	let first = solve();
+	assert_eq!(first, solve());
+
+	let evolved = run(first);
+	let initial = run(initial_rotation.clone());
+	assert!(evolved.success);
+	assert!(evolved.simulation.quality > initial.simulation.quality);
+
+	// a fitness can use the caller's own target
+	let target = initial.simulation.quality + 1;
+	let rotation = GeneticSolver::builder()
+		.recipe(recipe.clone())
+		.crafter_stats(stats.clone())
+		.initial_rotation(initial_rotation.clone())
+		.population_size(40)
+		.generations(40)
+		.max_length(15)
+		.fitness(Arc::new(move |result: &SimulationResult| {
+			let reached = result.simulation.quality.min(target) as f64 / target as f64;
+			if result.success {
+				1.0 + reached
+			} else {
+				reached
+			}
+		}))
+		.seed(11)
+		.build()
+		.solve()
+		.unwrap();
+	assert!(run(rotation).simulation.quality >= target);
+
+	// rates out of range are clamped rather than drawn
+	let rotation = GeneticSolver::builder()
+		.recipe(recipe.clone())
+		.crafter_stats(stats.clone())
+		.population_size(10)
+		.generations(10)
+		.mutation_rate(2.0)
+		.crossover_rate(-1.0)
+		.seed(11)
+		.build()
+		.solve();
+	assert!(rotation.map_or(true, |rotation| run(rotation).success));
+
+	// nothing can be crafted before any action is unlocked
+	let rotation = GeneticSolver::builder()
+		.recipe(recipe.clone())
+		.crafter_stats(CrafterStats {
+			level: CraftingLevel::unchecked_new(0),
+			..stats.clone()
+		})
+		.initial_rotation(initial_rotation)
+		.seed(11)
+		.build()
+		.solve();
+	assert_eq!(rotation, None);
+
+	Ok(())
+}
+
+#[test]
+fn test_mcts_planner() -> Result<()> {
+	use crate::solver::{default_reward, quality_target, quality_target_reward};
+	use crate::types::structs::CollectableTier;
+
+	// generateRecipe(480, 6178, 36208, 110, 90, 115)
+	let recipe = Craft {
+		expert: Some(true),
+		conditions_flag: 115,
+		..generate_recipe_rlvl(3864, 80, 480, 80, 6178, 36208, 110, 90)
+	};
+	let stats = generate_stats(80, 2745, 2885, 626);
+	let mut sim = Simulation::builder()
+		.recipe(recipe)
+		.crafter_stats(stats)
+		.build();
+	// one more step of durability and no CP left, one synthesis away from completion
+	sim.progression = 6100;
+	sim.durability = 10;
+	sim.available_cp = 0;
+
+	let plan = |seed: u64| {
+		MctsPlanner::builder()
+			.simulation(sim.clone())
+			.state(StepState::Sturdy)
+			.iterations(300)
+			.seed(seed)
+			.build()
+			.plan()
+	};
+
+	let result = plan(5);
+	assert_eq!(result.iterations, 300);
+	assert_eq!(
+		result.actions.iter().map(|stats| stats.visits).sum::<u32>(),
+		300
+	);
+	assert_eq!(result.best_action(), Some(actions::BasicSynthesis.into()));
+	assert_eq!(
+		plan(5).actions.iter().map(|stats| stats.visits).collect::<Vec<_>>(),
+		result.actions.iter().map(|stats| stats.visits).collect::<Vec<_>>()
+	);
+
+	// rewards can aim for just the quality the caller needs
+	assert_eq!(quality_target(&sim.recipe), 36208);
+	let required = Craft {
+		required_quality: Some(20000),
+		..sim.recipe.clone()
+	};
+	assert_eq!(quality_target(&required), 20001);
+	let collectable = Craft {
+		collectable_tiers: Some(vec![
+			CollectableTier {
+				collectability: 1000,
+				..Default::default()
+			},
+			CollectableTier {
+				collectability: 1500,
+				..Default::default()
+			},
+		]),
+		..sim.recipe.clone()
+	};
+	assert_eq!(quality_target(&collectable), 15000);
+
+	let mut finished = sim.clone();
+	finished.progression = finished.recipe.progress;
+	finished.quality = 15000;
+	let reward = quality_target_reward(15000);
+	assert_eq!(reward(&finished), 1.0);
+	assert!(default_reward(&finished) < 1.0);
+	let result = MctsPlanner::builder()
+		.simulation(sim.clone())
+		.iterations(50)
+		.seed(5)
+		.reward(reward)
+		.build()
+		.plan();
+	assert_eq!(result.iterations, 50);
+
+	Ok(())
+}
+
+#[test]
+fn test_macro_export() -> Result<()> {
+	let mut rotation: Vec<CraftingActionEnum> =
+		vec![actions::MuscleMemory.into(), actions::Veneration.into()];
+	rotation.extend(std::iter::repeat(CraftingActionEnum::from(actions::BasicTouch)).take(12));
+	rotation.push(actions::ByregotsBlessing.into());
+	rotation.push(actions::BasicSynthesis.into());
+
+	let macros = MacroExporter::default().export(&rotation);
+	assert_eq!(macros.len(), 2);
+	assert_eq!(macros[0].lines().count(), 15);
+	assert_eq!(
+		macros[0].lines().take(2).collect::<Vec<_>>(),
+		vec!["/ac \"Muscle Memory\" <wait.3>", "/ac \"Veneration\" <wait.2>"]
+	);
+	assert_eq!(macros[1], "/ac \"Basic Synthesis\" <wait.3>");
+
+	let macros = MacroExporter::builder()
+		.wait_padding(1)
+		.macro_lock(true)
+		.echo(true)
+		.echo_sound(1)
+		.final_wait(false)
+		.build()
+		.export(&rotation);
+	assert_eq!(macros.len(), 2);
+	let first: Vec<_> = macros[0].lines().collect();
+	assert_eq!(first.len(), 15);
+	assert_eq!(first[0], "/macrolock");
+	assert_eq!(first[1], "/ac \"Muscle Memory\" <wait.4>");
+	assert_eq!(first[14], "/echo Macro #1 finished <se.1>");
+	let second: Vec<_> = macros[1].lines().collect();
+	assert_eq!(
+		second,
+		vec![
+			"/macrolock",
+			"/ac \"Basic Touch\" <wait.4>",
+			"/ac \"Byregot's Blessing\" <wait.4>",
+			"/ac \"Basic Synthesis\"",
+			"/echo Craft finished <se.1>",
+		]
+	);
+
+	Ok(())
+}
+
+#[test]
+fn test_macro_import() -> Result<()> {
+	let text = "/macrolock
+/ac \"Muscle Memory\" <wait.3>
+/action Veneration <wait.2>
+// opener done
+/ac 下地作業 <wait.3>
+/aktion \"Byregots Benediktion\" <wait.3>
+/Aktion Innovation <wait.2>
+/ac \"Œil de l’expert\" <wait.3>
+/statusoff \"Final Appraisal\"
+/echo Macro #1 finished <se.1>";
+	let rotation = import_macro(text)?;
+	assert_eq!(
+		rotation,
+		vec![
+			actions::MuscleMemory.into(),
+			actions::Veneration.into(),
+			actions::Groundwork.into(),
+			actions::ByregotsBlessing.into(),
+			actions::Innovation.into(),
+			actions::TrainedEye.into(),
+			actions::RemoveFinalAppraisal.into(),
+		]
+	);
+
+	let exported = MacroExporter::default().export(&rotation).join("\n");
+	assert_eq!(import_macro(&exported)?, rotation);
+
+	assert!(is_action_command("/AKTION"));
+	assert!(!is_action_command("/echo"));
+
+	let error = import_macro("/ac \"Basic Touch\"\n/ac \"Inner Quiet\" <wait.2>").unwrap_err();
+	assert_eq!(
+		error.unknown_actions,
+		vec![UnknownAction {
+			line: 2,
+			name: "Inner Quiet".to_string()
+		}]
+	);
+
+	Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_round_trip() -> Result<()> {
+	use crate::types::structs::SimulationResultView;
+
+	let recipe = generate_recipe_rlvl(3864, 80, 480, 80, 6178, 36208, 110, 90);
+	let stats = generate_stats(80, 2745, 2885, 626);
+	let rotation: Vec<CraftingActionEnum> = vec![
+		actions::Reflect.into(),
+		actions::WasteNotII.into(),
+		actions::ByregotsBlessing.into(),
+	];
+
+	let json = serde_json::to_string(&rotation)?;
+	assert_eq!(json, r#"["Reflect","WasteNotII","ByregotsBlessing"]"#);
+	assert_eq!(serde_json::from_str::<Vec<CraftingActionEnum>>(&json)?, rotation);
+	assert!(serde_json::from_str::<CraftingActionEnum>(r#""InnerQuiet""#).is_err());
+
+	let recipe: Craft = serde_json::from_str(&serde_json::to_string(&recipe)?)?;
+	let stats: CrafterStats = serde_json::from_str(&serde_json::to_string(&stats)?)?;
+	assert_eq!(recipe.rlvl, 480);
+	assert_eq!(stats.level, 80);
+	assert!(serde_json::from_str::<CraftingLevel>("101").is_err());
+
+	let result = Simulation::builder()
+		.recipe(recipe)
+		.crafter_stats(stats)
+		.actions(rotation)
+		.build()
+		.start()
+		.linear(true)
+		.run();
+	let view: SimulationResultView =
+		serde_json::from_str(&serde_json::to_string(&SimulationResultView::from(&result))?)?;
+	assert_eq!(view.quality, result.simulation.quality);
+	assert_eq!(view.steps.len(), 3);
+	assert!(view.steps[2].action == actions::ByregotsBlessing.into());
+
+	Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_teamcraft_rotation() -> Result<()> {
+	use crate::teamcraft::{
+		export_rotation, import_rotation, import_saved_rotations, TeamcraftImportError,
+		TeamcraftSavedRotation, UnknownRotationAction,
+	};
+
+	let rotation: Vec<CraftingActionEnum> = vec![
+		actions::MuscleMemory.into(),
+		actions::Manipulation.into(),
+		actions::BasicTouch.into(),
+		actions::ByregotsBlessing.into(),
+	];
+	let json = export_rotation(&rotation);
+	assert_eq!(
+		json,
+		r#"["MuscleMemory","Manipulation","BasicTouch","ByregotsBlessing"]"#
+	);
+	assert_eq!(import_rotation(&json)?, rotation);
+
+	let saved = r#"{
+		"$key": "abc123",
+		"name": "Old 80* rotation",
+		"rotation": ["MuscleMemory", "InnerQuiet", "BasicTouch", "FocusedTouch"],
+		"recipe": { "rlvl": 480, "durability": 80 },
+		"public": false
+	}"#;
+	match import_rotation(saved) {
+		Err(TeamcraftImportError::UnknownActions(unknown)) => assert_eq!(
+			unknown,
+			vec![
+				UnknownRotationAction {
+					index: 1,
+					name: "InnerQuiet".to_string(),
+				},
+				UnknownRotationAction {
+					index: 3,
+					name: "FocusedTouch".to_string(),
+				},
+			]
+		),
+		_ => panic!("expected unknown actions"),
+	}
+	assert!(matches!(
+		import_rotation("{\"name\": 3}"),
+		Err(TeamcraftImportError::Json(_))
+	));
+
+	let saved_rotations = import_saved_rotations(&format!("[{}]", saved))?;
+	assert_eq!(saved_rotations.len(), 1);
+	assert_eq!(saved_rotations[0].key.as_deref(), Some("abc123"));
+	assert_eq!(saved_rotations[0].extra["recipe"]["rlvl"], 480);
+	let round_trip: TeamcraftSavedRotation =
+		serde_json::from_str(&serde_json::to_string(&saved_rotations[0])?)?;
+	assert_eq!(round_trip, saved_rotations[0]);
+
+	let new_rotation = TeamcraftSavedRotation::new(Some("New".to_string()), &rotation);
+	assert_eq!(new_rotation.actions()?, rotation);
+	assert_eq!(
+		import_rotation(&serde_json::to_string(&new_rotation)?)?,
+		rotation
+	);
+
+	Ok(())
+}
+
+#[test]
+fn test_recipe_level_table() -> Result<()> {
+	let recipe = Craft::from_recipe_level()
+		.rlvl(560)
+		.durability(80)
+		.progress(1000)
+		.quality(5200)
+		.build()?;
+	assert_eq!(recipe.lvl, 90);
+	assert_eq!(recipe.progress_divider, 130);
+	assert_eq!(recipe.quality_divider, 115);
+	assert_eq!(recipe.progress_modifier, Some(90.0));
+	assert_eq!(recipe.quality_modifier, Some(80.0));
+	assert_eq!(recipe.stars, None);
+	assert!(recipe.check_recipe_level().is_ok());
+
+	assert_eq!(
+		Craft::from_recipe_level()
+			.rlvl(559)
+			.durability(80)
+			.progress(1000)
+			.quality(5200)
+			.build()
+			.err(),
+		Some(RecipeLevelError::UnknownRecipeLevel(559))
+	);
+	assert!(matches!(
+		Craft::from_recipe_level()
+			.rlvl(560)
+			.stars(2)
+			.durability(80)
+			.progress(1000)
+			.quality(5200)
+			.build(),
+		Err(RecipeLevelError::Mismatches(mismatches)) if mismatches[0].field == "stars"
+	));
+
+	// the requested rlvl is kept, even when another row of its level has those stars
+	assert!(matches!(
+		Craft::from_recipe_level()
+			.rlvl(560)
+			.stars(4)
+			.durability(70)
+			.progress(6600)
+			.quality(14040)
+			.build(),
+		Err(RecipeLevelError::Mismatches(mismatches))
+			if mismatches == vec![RecipeLevelMismatch { field: "stars", expected: 0.0, actual: 4.0 }]
+	));
+	let recipe = Craft::from_recipe_level()
+		.rlvl(640)
+		.stars(4)
+		.durability(70)
+		.progress(6600)
+		.quality(14040)
+		.build()?;
+	assert_eq!(recipe.rlvl, 640);
+	assert_eq!(recipe.stars, Some(4));
+	assert_eq!(recipe.quality_modifier, Some(70.0));
+	let recipe = Craft::from_recipe_level()
+		.rlvl(480)
+		.durability(80)
+		.progress(6178)
+		.quality(36208)
+		.build()?;
+	assert_eq!((u8::from(recipe.lvl), recipe.stars), (80, Some(4)));
+
+	// hand-filled recipes can be checked too
+	let recipe = generate_recipe_rlvl(3864, 81, 517, 80, 2000, 5200, 120, 105);
+	assert_eq!(
+		recipe.check_recipe_level(),
+		Err(RecipeLevelError::Mismatches(vec![RecipeLevelMismatch {
+			field: "progress_divider",
+			expected: 121.0,
+			actual: 120.0,
+		}]))
+	);
+
+	let mut table = RecipeLevelTable::new();
+	table.insert(RecipeLevel {
+		rlvl: 640,
+		class_job_level: CraftingLevel::unchecked_new(90),
+		stars: 4,
+		progress_divider: 130,
+		quality_divider: 115,
+		progress_modifier: 80,
+		quality_modifier: 70,
+		conditions_flag: 15,
+	});
+	let recipe = Craft::from_recipe_level()
+		.rlvl(640)
+		.durability(70)
+		.progress(6600)
+		.quality(14040)
+		.table(&table)
+		.build()?;
+	assert_eq!(recipe.stars, Some(4));
+	assert_eq!(table.check(&recipe), Ok(()));
+
+	// expert rows keep their own conditions
+	let table = RecipeLevelTable::from_reader(
+		"\
+#,ClassJobLevel,Stars,ProgressDivider,QualityDivider,ProgressModifier,QualityModifier,ConditionsFlag
+640,90,4,130,115,80,70,483
+"
+		.as_bytes(),
+	)?;
+	assert_eq!(table.get(640).map(|row| row.conditions_flag), Some(483));
+	assert_eq!(
+		RecipeLevelTable::builtin()
+			.get(285)
+			.map(|row| u8::from(row.class_job_level)),
+		Some(68)
+	);
+
+	Ok(())
+}
+
+#[test]
+fn test_recipe_database() -> Result<()> {
+	let recipe_csv = "\
+key,0,1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21,22,23
+#,Number,CraftType,RecipeLevelTable,Item{Result},Amount{Result},Item{Ingredient}[0],Amount{Ingredient}[0],Item{Ingredient}[1],Amount{Ingredient}[1],Item{Ingredient}[2],Amount{Ingredient}[2],MaterialQualityFactor,DifficultyFactor,QualityFactor,DurabilityFactor,RequiredQuality,RequiredCraftsmanship,RequiredControl,SecretRecipeBook,CanQuickSynth,CanHq,IsExpert,PatchNumber
+int32,int32,CraftType,RecipeLevelTable,Item,byte,Item,byte,Item,byte,Item,byte,byte,uint16,uint16,uint16,uint32,uint16,uint16,SecretRecipeBook,bit&01,bit&02,bit&04,uint16
+0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,False,False,False,0
+35000,1,1,560,100,3,200,2,201,1,8,4,50,100,200,100,0,0,0,12,True,True,False,600
+35001,2,7,560,100,1,200,1,0,0,0,0,50,50,100,50,800,3000,2900,0,False,False,True,600
+";
+	let recipe_level_csv = "\
+key,0,1,2,3,4,5,6,7,8,9,10
+#,ClassJobLevel,Stars,SuggestedCraftsmanship,Difficulty,Quality,ProgressDivider,QualityDivider,ProgressModifier,QualityModifier,Durability,ConditionsFlag
+int32,byte,byte,uint16,uint16,uint32,byte,byte,byte,byte,uint16,uint16
+0,0,0,0,0,0,0,0,0,0,0,0
+560,90,0,2600,3500,7200,130,115,90,80,80,15
+";
+	let item_csv = "\
+key,0,1,2,3
+#,Name,Level{Item},CanBeHq,Rarity
+int32,str,ItemLevel,bit&01,byte
+0,,0,False,0
+8,Fire Shard,1,False,1
+100,Chondrite Saw,560,True,1
+200,Chondrite Ingot,560,True,1
+201,Integral Lumber,580,True,1
+";
+	let database = RecipeDatabase::from_readers(
+		recipe_csv.as_bytes(),
+		recipe_level_csv.as_bytes(),
+		item_csv.as_bytes(),
+	)?;
+
+	let recipe = database.recipe(35000).unwrap();
+	assert_eq!(recipe.job, 9);
+	assert_eq!(recipe.lvl, 90);
+	assert_eq!(recipe.progress, 3500);
+	assert_eq!(recipe.quality, 14400);
+	assert_eq!(recipe.durability, 80);
+	assert_eq!(recipe.r#yield, Some(3));
+	assert_eq!(recipe.unlock_id, Some(12));
+	assert_eq!(recipe.suggested_craftsmanship, Some(2600));
+	assert_eq!(recipe.required_quality, None);
+	assert_eq!(recipe.expert, Some(false));
+	assert_eq!((recipe.progress_divider, recipe.quality_divider), (130, 115));
+	assert_eq!(recipe.quality_modifier, Some(80.0));
+	assert!(recipe.check_recipe_level().is_ok());
+	assert_eq!(recipe.max_initial_quality(), 7200);
+	// half of the quality is split by item level between the three HQ-able items
+	let qualities: Vec<_> = recipe
+		.ingredients
+		.iter()
+		.map(|ingredient| (ingredient.id.as_str(), ingredient.amount, ingredient.quality))
+		.collect();
+	assert_eq!(
+		qualities,
+		vec![
+			("200", 2, Some(2371)),
+			("201", 1, Some(2456)),
+			("8", 4, None)
+		]
+	);
+
+	let expert = database.recipe(35001).unwrap();
+	assert_eq!(expert.job, 15);
+	assert_eq!(expert.expert, Some(true));
+	assert_eq!(expert.required_quality, Some(800));
+	assert_eq!(expert.craftsmanship_req, Some(3000));
+	assert_eq!(expert.control_req, Some(2900));
+	assert_eq!(expert.hq, Some(false));
+
+	assert_eq!(database.recipes().count(), 2);
+	assert_eq!(database.recipes_for_item(100).len(), 2);
+	assert_eq!(database.recipes_for_item_name("chondrite saw").len(), 2);
+	assert!(database.recipes_for_item_name("Chondrite Ingot").is_empty());
+	assert_eq!(database.result_item(35001).unwrap().name, "Chondrite Saw");
+	assert_eq!(database.recipe_level_table().get(560).unwrap().progress_modifier, 90);
+
+	assert!(matches!(
+		RecipeDatabase::from_readers(
+			recipe_csv.as_bytes(),
+			recipe_level_csv.as_bytes(),
+			"#,Name\n1,Fire Shard".as_bytes(),
+		),
+		Err(RecipeDataError::MissingColumn { sheet: "Item", .. })
+	));
+
+	Ok(())
+}
+
+#[cfg(feature = "xivapi")]
+#[tokio::test]
+async fn test_xivapi_client() -> Result<()> {
+	use crate::xivapi::{XivapiClient, XivapiError};
+	use mockito::Matcher;
+
+	let mut server = mockito::Server::new_async().await;
+	let key = Matcher::UrlEncoded("private_key".to_string(), "secret".to_string());
+	let recipe = server
+		.mock("GET", "/sheet/Recipe/35000")
+		.match_query(key.clone())
+		.with_body(
+			r#"{"row_id": 35000, "fields": {
+				"CraftType": {"value": 1, "sheet": "CraftType", "row_id": 1, "fields": {}},
+				"RecipeLevelTable": {"value": 560, "sheet": "RecipeLevelTable", "row_id": 560},
+				"ItemResult": {"value": 100, "sheet": "Item", "row_id": 100},
+				"AmountResult": 1,
+				"Ingredient": [{"value": 200, "row_id": 200}, {"value": 0, "row_id": 0}],
+				"AmountIngredient": [2, 0],
+				"MaterialQualityFactor": 50, "DifficultyFactor": 100, "QualityFactor": 200,
+				"DurabilityFactor": 100, "RequiredQuality": 0, "RequiredCraftsmanship": 0,
+				"RequiredControl": 0, "SecretRecipeBook": {"value": 0, "row_id": 0},
+				"CanQuickSynth": true, "CanHq": true, "IsExpert": false
+			}}"#,
+		)
+		.expect(1)
+		.create_async()
+		.await;
+	let recipe_level = server
+		.mock("GET", "/sheet/RecipeLevelTable/560")
+		.match_query(key.clone())
+		.with_body(
+			r#"{"row_id": 560, "fields": {
+				"ClassJobLevel": 90, "Stars": 0, "SuggestedCraftsmanship": 2600,
+				"Difficulty": 3500, "Quality": 7200, "Durability": 80,
+				"ProgressDivider": 130, "QualityDivider": 115,
+				"ProgressModifier": 90, "QualityModifier": 80, "ConditionsFlag": 15
+			}}"#,
+		)
+		.expect(1)
+		.create_async()
+		.await;
+	let items = [
+		server
+			.mock("GET", "/sheet/Item/100")
+			.match_query(key.clone())
+			.with_body(r#"{"row_id": 100, "fields": {"Name": "Chondrite Saw", "LevelItem": {"value": 560}, "CanBeHq": true}}"#)
+			.expect(1)
+			.create_async()
+			.await,
+		server
+			.mock("GET", "/sheet/Item/200")
+			.match_query(key.clone())
+			.with_body(r#"{"row_id": 200, "fields": {"Name": "Chondrite Ingot", "LevelItem": {"value": 560}, "CanBeHq": true}}"#)
+			.expect(1)
+			.create_async()
+			.await,
+	];
+	server
+		.mock("GET", "/sheet/Recipe/1")
+		.match_query(key.clone())
+		.with_status(404)
+		.create_async()
+		.await;
+
+	let cache_dir = std::env::temp_dir().join(format!("byregox-xivapi-{}", std::process::id()));
+	let client = || {
+		XivapiClient::builder()
+			.base_url(format!("{}/", server.url()))
+			.api_key("secret".to_string())
+			.cache_dir(cache_dir.clone())
+			.build()
+	};
+
+	let craft = client().recipe(35000).await?;
+	assert_eq!(craft.job, 9);
+	assert_eq!(craft.lvl, 90);
+	assert_eq!((craft.progress, craft.quality, craft.durability), (3500, 14400, 80));
+	assert_eq!(craft.ingredients.len(), 1);
+	assert_eq!(craft.ingredients[0].quality, Some(3600));
+	assert!(craft.check_recipe_level().is_ok());
+
+	// served from the cache this time
+	let cached = client().recipe(35000).await?;
+	assert_eq!(cached.quality, craft.quality);
+	recipe.assert_async().await;
+	recipe_level.assert_async().await;
+	for item in items {
+		item.assert_async().await;
+	}
+
+	assert!(matches!(
+		client().recipe(1).await,
+		Err(XivapiError::Http(err)) if err.status() == Some(reqwest::StatusCode::NOT_FOUND)
+	));
+
+	std::fs::remove_dir_all(cache_dir)?;
+	Ok(())
+}
+
+#[cfg(feature = "market")]
+#[tokio::test]
+async fn test_market_estimate() -> Result<()> {
+	use crate::market::{ItemPrices, MarketClient};
+	use crate::types::structs::Ingredient;
+
+	let mut server = mockito::Server::new_async().await;
+	let prices = server
+		.mock("GET", "/Phoenix/8,100,200")
+		.with_body(
+			r#"{"itemIDs": [8, 100, 200], "unresolvedItems": [], "items": {
+				"8": {"itemID": 8, "minPriceNQ": 5, "minPriceHQ": 0},
+				"100": {"itemID": 100, "minPriceNQ": 1000, "minPriceHQ": 3000},
+				"200": {"itemID": 200, "minPriceNQ": 200, "minPriceHQ": 500}
+			}}"#,
+		)
+		.create_async()
+		.await;
+	server
+		.mock("GET", "/Phoenix/999")
+		.with_body(r#"{"itemID": 999, "minPriceNQ": 0, "minPriceHQ": 0, "listings": []}"#)
+		.create_async()
+		.await;
+	let client = MarketClient::builder()
+		.world("Phoenix".to_string())
+		.base_url(server.url())
+		.build();
+
+	let recipe = Craft {
+		r#yield: Some(3),
+		ingredients: vec![
+			Ingredient {
+				id: "200".to_string(),
+				amount: 2,
+				quality: Some(100),
+				..Default::default()
+			},
+			Ingredient {
+				id: "8".to_string(),
+				amount: 4,
+				..Default::default()
+			},
+		],
+		..generate_recipe_lvl(3864, 16, 80, 31, 866, 50, 30)
+	};
+	let mut sim = Simulation::builder()
+		.recipe(recipe)
+		.crafter_stats(generate_stats(90, 4000, 4000, 600))
+		.actions(vec![])
+		.build();
+	// 50% quality is a 15% HQ chance
+	sim.quality = 433;
+
+	let hq_ingredients = [Ingredient {
+		id: "200".to_string(),
+		amount: 1,
+		..Default::default()
+	}];
+	let estimate = client
+		.estimate(&sim, 100, None, Some(&hq_ingredients))
+		.await?;
+	prices.assert_async().await;
+	assert_eq!(estimate.hq_percent, 15);
+	assert_eq!(estimate.materials.len(), 3);
+	assert_eq!(estimate.material_cost, 200 + 500 + 4 * 5);
+	assert!(estimate.missing_prices.is_empty());
+	assert_eq!(estimate.sale_value, 3.0 * (0.15 * 3000.0 + 0.85 * 1000.0));
+	assert_eq!(estimate.profit, estimate.sale_value - 720.0);
+
+	// the ingot is crafted from shards and ore instead of being bought
+	let tree_prices = server
+		.mock("GET", "/Phoenix/8,100,300")
+		.with_body(
+			r#"{"itemIDs": [8, 100, 300], "unresolvedItems": [], "items": {
+				"8": {"itemID": 8, "minPriceNQ": 5, "minPriceHQ": 0},
+				"100": {"itemID": 100, "minPriceNQ": 1000, "minPriceHQ": 3000},
+				"300": {"itemID": 300, "minPriceNQ": 40, "minPriceHQ": 0}
+			}}"#,
+		)
+		.create_async()
+		.await;
+	let recipes: HashMap<String, Craft> = [(
+		"200".to_string(),
+		Craft {
+			id: "r200".to_string(),
+			job: 9,
+			ingredients: vec![
+				Ingredient {
+					id: "8".to_string(),
+					amount: 3,
+					..Default::default()
+				},
+				Ingredient {
+					id: "300".to_string(),
+					amount: 1,
+					..Default::default()
+				},
+			],
+			..generate_recipe_lvl(3864, 16, 80, 31, 866, 50, 30)
+		},
+	)]
+	.into_iter()
+	.collect();
+	let estimate = client.estimate_tree(&sim, 100, &recipes, None).await?;
+	tree_prices.assert_async().await;
+	let materials: Vec<_> = estimate
+		.materials
+		.iter()
+		.map(|cost| (cost.item_id, cost.amount))
+		.collect();
+	assert_eq!(materials, vec![(8, 10), (300, 2)]);
+	assert_eq!(estimate.material_cost, 10 * 5 + 2 * 40);
+	assert_eq!(
+		estimate
+			.intermediate_crafts
+			.iter()
+			.map(|entry| (entry.item_id.as_str(), entry.crafts))
+			.collect::<Vec<_>>(),
+		vec![("200", 2)]
+	);
+
+	assert_eq!(
+		client.prices(&[999]).await?.get(&999),
+		Some(&ItemPrices { nq: None, hq: None })
+	);
+
+	Ok(())
+}
+
+#[test]
+fn test_initial_quality() -> Result<()> {
+	use crate::types::structs::Ingredient;
+
+	let ingredient = |id: &str, amount: u32, ilvl: Option<u32>| Ingredient {
+		id: id.to_string(),
+		amount,
+		ilvl,
+		..Default::default()
+	};
+	let recipe = Craft {
+		quality: 10000,
+		material_quality_factor: Some(50),
+		ingredients: vec![
+			ingredient("200", 2, Some(560)),
+			ingredient("201", 1, Some(580)),
+			ingredient("8", 4, None),
+		],
+		..generate_recipe_rlvl(3864, 90, 560, 80, 3500, 10000, 130, 115)
+	};
+
+	assert_eq!(recipe.max_initial_quality(), 5000);
+	assert_eq!(recipe.initial_quality(&[]), 0);
+	// 10000 * 50% * (560 / 1700)
+	assert_eq!(recipe.initial_quality(&[ingredient("200", 1, None)]), 1647);
+	assert_eq!(recipe.initial_quality(&[ingredient("201", 1, None)]), 1705);
+	// more HQ items than the recipe uses don't add anything
+	assert_eq!(
+		recipe.initial_quality(&[ingredient("200", 5, None), ingredient("201", 1, None)]),
+		5000
+	);
+
+	let sim = Simulation::builder()
+		.recipe(recipe.clone())
+		.crafter_stats(generate_stats(90, 4000, 4000, 600))
+		.hq_ingredients(vec![ingredient("200", 2, None)])
+		.actions(vec![actions::BasicSynthesis.into()])
+		.build();
+	assert_eq!(sim.quality, 3294);
+
+	// flat qualities without item levels, still capped by the material quality factor
+	let recipe = Craft {
+		ingredients: vec![Ingredient {
+			quality: Some(3000),
+			..ingredient("200", 2, None)
+		}],
+		..recipe
+	};
+	assert_eq!(recipe.initial_quality(&[ingredient("200", 1, None)]), 3000);
+	assert_eq!(recipe.max_initial_quality(), 5000);
+
+	Ok(())
+}
+
+#[test]
+fn test_hq_ingredient_solver() -> Result<()> {
+	use crate::types::structs::Ingredient;
+
+	let ingredient = |id: &str, amount: u32, ilvl: Option<u32>| Ingredient {
+		id: id.to_string(),
+		amount,
+		ilvl,
+		..Default::default()
+	};
+	let recipe = Craft {
+		material_quality_factor: Some(50),
+		ingredients: vec![
+			ingredient("200", 2, Some(560)),
+			ingredient("201", 1, Some(580)),
+			ingredient("8", 4, None),
+		],
+		..generate_recipe_rlvl(3864, 90, 560, 80, 100, 10000, 130, 115)
+	};
+	let stats = generate_stats(90, 4000, 4000, 600);
+	let rotation: Vec<CraftingActionEnum> =
+		vec![actions::BasicTouch.into(), actions::BasicSynthesis.into()];
+	let rotation_quality = Simulation::builder()
+		.recipe(recipe.clone())
+		.crafter_stats(stats.clone())
+		.actions(rotation.clone())
+		.build()
+		.start()
+		.linear(true)
+		.run()
+		.simulation
+		.quality;
+	let hq_costs = HashMap::from([
+		("200".to_string(), 100),
+		("201".to_string(), 500),
+		("8".to_string(), 1),
+	]);
+	let solve = |target_quality: u32| {
+		HqIngredientSolver::builder()
+			.recipe(recipe.clone())
+			.crafter_stats(stats.clone())
+			.actions(rotation.clone())
+			.hq_costs(hq_costs.clone())
+			.target_quality(target_quality)
+			.build()
+			.solve()
+	};
+	let hq_amounts = |plan: &HqIngredientPlan| {
+		plan.hq_ingredients
+			.iter()
+			.map(|ingredient| (ingredient.id.clone(), ingredient.amount))
+			.collect::<Vec<_>>()
+	};
+
+	let plan = solve(rotation_quality).unwrap();
+	assert!(plan.hq_ingredients.is_empty());
+	assert_eq!(plan.cost, 0);
+
+	// one HQ "200" adds 1647 quality
+	let plan = solve(rotation_quality + 1600).unwrap();
+	assert_eq!(hq_amounts(&plan), vec![("200".to_string(), 1)]);
+	assert_eq!(plan.cost, 100);
+	assert_eq!(plan.initial_quality, 1647);
+	assert_eq!(plan.quality, rotation_quality + 1647);
+	assert!(plan.success);
+
+	// a single "201" would do, but two "200" are cheaper
+	let plan = solve(rotation_quality + 1700).unwrap();
+	assert_eq!(hq_amounts(&plan), vec![("200".to_string(), 2)]);
+	assert_eq!(plan.cost, 200);
+
+	let plan = solve(rotation_quality + 5000).unwrap();
+	assert_eq!(plan.cost, 700);
+	assert_eq!(plan.initial_quality, 5000);
+	assert!(solve(rotation_quality + 5001).is_none());
+
+	Ok(())
+}
+
+#[test]
+fn test_collectables() -> Result<()> {
+	use crate::types::structs::CollectableTier;
+
+	let tier = |collectability: u32, reward: u32| CollectableTier {
+		collectability,
+		reward,
+		exp: None,
+	};
+	let recipe = Craft {
+		collectable_tiers: Some(vec![tier(300, 10), tier(350, 15), tier(450, 25)]),
+		..generate_recipe_lvl(3864, 16, 80, 31, 5000, 50, 30)
+	};
+	let stats = generate_stats(80, 2278, 2348, 532);
+	let rotation: Vec<CraftingActionEnum> = vec![
+		actions::Reflect.into(),
+		actions::BasicTouch.into(),
+		actions::CarefulSynthesis.into(),
+	];
+	let run = |actions: Vec<CraftingActionEnum>| {
+		Simulation::builder()
+			.recipe(recipe.clone())
+			.crafter_stats(stats.clone())
+			.actions(actions)
+			.build()
+			.start()
+			.linear(true)
+			.run()
+	};
+
+	let result = run(rotation.clone());
+	assert_eq!(result.simulation.quality, 3431);
+	assert_eq!(result.collectability, 343);
+	assert_eq!(result.collectable_tier, Some(0));
+	assert_eq!(recipe.collectable_tier(449), Some(1));
+	assert_eq!(recipe.collectable_tier(299), None);
+
+	// quality past the recipe's maximum doesn't count
+	let mut capped = result.simulation.clone();
+	capped.recipe.quality = 3000;
+	assert_eq!(capped.get_collectability(), 300);
+
+	// no tier without completing the craft
+	let result = run(rotation[..2].to_vec());
+	assert!(!result.success);
+	assert_eq!(result.collectable_tier, None);
+
+	// good and excellent conditions can push the craft into a higher tier
+	let exact = ExactEvaluator::builder()
+		.recipe(recipe.clone())
+		.crafter_stats(stats.clone())
+		.actions(rotation.clone())
+		.build()
+		.run();
+	let distribution = exact.collectable_tier_distribution();
+	assert!((distribution.values().sum::<f64>() - 1.0).abs() < 1e-9);
+	assert!(distribution[&0] > distribution[&1]);
+
+	let monte_carlo = MonteCarlo::builder()
+		.recipe(recipe)
+		.crafter_stats(stats)
+		.actions(rotation)
+		.runs(5000)
+		.seed(18)
+		.build()
+		.run();
+	for (&tier, &probability) in &distribution {
+		assert!((monte_carlo.collectable_tier_rate(tier) - probability).abs() < 0.03);
+	}
+	assert_eq!(monte_carlo.collectable_tier_at_least_rate(0), 1.0);
+	assert!(
+		(monte_carlo.collectable_tier_at_least_rate(1)
+			- monte_carlo.collectable_tier_rate(1)
+			- monte_carlo.collectable_tier_rate(2))
+		.abs() < 1e-9
+	);
+
+	Ok(())
+}
+
+#[test]
+fn test_workshop_planner() -> Result<()> {
+	use crate::types::structs::Ingredient;
+	use crate::workshop::{TeamMember, WorkshopProject, WorkshopStep};
+
+	let step = |part: &str, phase: u32, lvl: u8, ingredients: &[(&str, u32)]| {
+		let recipe = Craft {
+			job: 0,
+			control_req: Some(if lvl > 50 { 2000 } else { 0 }),
+			ingredients: ingredients
+				.iter()
+				.map(|&(id, amount)| Ingredient {
+					id: id.to_string(),
+					amount,
+					step_id: Some(format!("{}-{}", part, phase)),
+					part: Some(part.to_string()),
+					phase: Some(phase),
+					..Default::default()
+				})
+				.collect(),
+			..generate_recipe_lvl(3864, lvl, 80, 100, 1000, 50, 30)
+		};
+		WorkshopStep::from_recipe(recipe, 3).remove(0)
+	};
+	let mut project = WorkshopProject::new(
+		"Shark-class Pressure Hull".to_string(),
+		vec![
+			step("Hull", 1, 40, &[("5057", 2), ("5371", 1)]),
+			step("Bulkhead", 1, 40, &[("5057", 1)]),
+			step("Stern", 1, 40, &[("5371", 2)]),
+			step("Bow", 1, 40, &[("5057", 1)]),
+			step("Hull", 2, 60, &[("5058", 4)]),
+		],
+	);
+	assert_eq!(project.steps[4].step_id.as_deref(), Some("Hull-2"));
+	assert_eq!(project.phases(), vec![1, 2]);
+
+	let materials: Vec<_> = project
+		.total_materials()
+		.into_iter()
+		.map(|ingredient| (ingredient.id, ingredient.amount))
+		.collect();
+	assert_eq!(
+		materials,
+		vec![
+			("5057".to_string(), 12),
+			("5371".to_string(), 9),
+			("5058".to_string(), 12)
+		]
+	);
+
+	assert!(project.record_craft("Hull", 1, Some("Hull-1"), 3));
+	assert!(project.record_craft("Bulkhead", 1, Some("Bulkhead-1"), 5));
+	assert!(!project.record_craft("Bulkhead", 2, Some("Bulkhead-1"), 1));
+	assert!(!project.record_craft("Stern", 1, None, 1));
+	assert_eq!(project.current_phase(), Some(1));
+	assert!((project.phase_progress(1) - 0.5).abs() < 1e-9);
+	assert!((project.progress() - 6.0 / 15.0).abs() < 1e-9);
+
+	let team = [
+		TeamMember {
+			name: "Alisaie".to_string(),
+			stats: generate_stats(70, 1500, 1500, 400),
+		},
+		TeamMember {
+			name: "Alphinaud".to_string(),
+			stats: generate_stats(90, 3000, 3000, 600),
+		},
+	];
+	let checklists = project.plan(&team);
+	assert_eq!(checklists.len(), 2);
+	assert!(!checklists[0].is_done());
+	// the better crafter takes the first open part, then the work is shared
+	assert_eq!(
+		checklists[0].to_string(),
+		"Phase 1\n\
+		- [x] Hull (3/3) - Alphinaud\n\
+		- [x] Bulkhead (3/3) - Alphinaud\n\
+		- [ ] Stern (0/3) - Alphinaud: 6x 5371\n\
+		- [ ] Bow (0/3) - Alisaie: 3x 5057\n"
+	);
+	// only Alphinaud meets the control requirement of phase 2
+	assert_eq!(
+		checklists[1].to_string(),
+		"Phase 2\n- [ ] Hull (0/3) - Alphinaud: 12x 5058\n"
+	);
+
+	// a recipe covering several parts and phases is split into one step for each
+	let tagged = |id: &str, part: &str, phase: u32| Ingredient {
+		id: id.to_string(),
+		amount: 1,
+		step_id: Some(format!("{}-{}", part, phase)),
+		part: Some(part.to_string()),
+		phase: Some(phase),
+		..Default::default()
+	};
+	let recipe = Craft {
+		ingredients: vec![
+			tagged("5058", "Hull", 2),
+			tagged("5057", "Hull", 1),
+			tagged("5371", "Stern", 1),
+			tagged("5371", "Hull", 1),
+		],
+		..generate_recipe_lvl(3864, 40, 80, 100, 1000, 50, 30)
+	};
+	let steps: Vec<_> = WorkshopStep::from_recipe(recipe, 3)
+		.into_iter()
+		.map(|step| {
+			let ingredients: Vec<_> = step
+				.recipe
+				.ingredients
+				.iter()
+				.map(|ingredient| ingredient.id.clone())
+				.collect();
+			(step.phase, step.part, ingredients)
+		})
+		.collect();
+	assert_eq!(
+		steps,
+		vec![
+			(1, "Hull".to_string(), vec!["5057".to_string(), "5371".to_string()]),
+			(1, "Stern".to_string(), vec!["5371".to_string()]),
+			(2, "Hull".to_string(), vec!["5058".to_string()]),
+		]
+	);
+
+	// two steps of the same part and phase are told apart by their step_id
+	let with_step_id = |id: &str, step_id: &str| Ingredient {
+		step_id: Some(step_id.to_string()),
+		..tagged(id, "Hull", 1)
+	};
+	let recipe = Craft {
+		ingredients: vec![with_step_id("5057", "hull-a"), with_step_id("5371", "hull-b")],
+		..generate_recipe_lvl(3864, 40, 80, 100, 1000, 50, 30)
+	};
+	let mut project = WorkshopProject::new(
+		"Hull plating".to_string(),
+		WorkshopStep::from_recipe(recipe, 3),
+	);
+	assert!(project.record_craft("Hull", 1, Some("hull-b"), 2));
+	let completed: Vec<_> = project
+		.steps
+		.iter()
+		.map(|step| (step.step_id.as_deref(), step.completed))
+		.collect();
+	assert_eq!(completed, vec![(Some("hull-a"), 0), (Some("hull-b"), 2)]);
+	assert!(!project.record_craft("Hull", 1, Some("hull-c"), 1));
+
+	Ok(())
+}
+
+#[test]
+fn test_crafting_list() {
+	use crate::types::structs::Ingredient;
+
+	let recipe = |id: &str, job: u32, r#yield: u32, ingredients: &[(&str, u32)]| Craft {
+		id: id.to_string(),
+		job,
+		r#yield: Some(r#yield),
+		ingredients: ingredients
+			.iter()
+			.map(|&(id, amount)| Ingredient {
+				id: id.to_string(),
+				amount,
+				..Default::default()
+			})
+			.collect(),
+		..generate_recipe_lvl(3864, 80, 395, 100, 1000, 50, 30)
+	};
+	// both finished items use the ingot, crafted three at a time
+	let recipes: HashMap<String, Craft> = [
+		("ring", recipe("r1", 11, 1, &[("ingot", 3), ("gem", 1)])),
+		("bracelet", recipe("r2", 12, 1, &[("ingot", 2), ("leather", 1)])),
+		("ingot", recipe("r3", 9, 3, &[("ore", 2), ("shard", 1)])),
+	]
+	.into_iter()
+	.map(|(item, recipe)| (item.to_string(), recipe))
+	.collect();
+
+	let list = CraftingList::new(&recipes, &[("ring", 2), ("bracelet", 1)]);
+	let crafts: Vec<_> = list
+		.crafts
+		.iter()
+		.map(|entry| (entry.item_id.as_str(), entry.job, entry.crafts, entry.amount))
+		.collect();
+	assert_eq!(
+		crafts,
+		vec![
+			("ingot", CraftingJob::Blacksmith, 3, 8),
+			("bracelet", CraftingJob::Leatherworker, 1, 1),
+			("ring", CraftingJob::Goldsmith, 2, 2),
+		]
+	);
+	let raw: Vec<_> = list
+		.raw_materials
+		.iter()
+		.map(|ingredient| (ingredient.id.as_str(), ingredient.amount))
+		.collect();
+	assert_eq!(
+		raw,
+		vec![("gem", 2), ("leather", 1), ("ore", 6), ("shard", 3)]
+	);
+	assert_eq!(list.crafts_by_job()[&CraftingJob::Blacksmith].len(), 1);
+
+	// the ingot is crafted from scrap, itself crafted from an ingot: the ingot the
+	// scrap needs is bought instead of looping
+	let recipes: HashMap<String, Craft> = [
+		("ingot", recipe("r3", 9, 1, &[("ore", 2), ("scrap", 1)])),
+		("scrap", recipe("r4", 9, 5, &[("ingot", 1)])),
+	]
+	.into_iter()
+	.map(|(item, recipe)| (item.to_string(), recipe))
+	.collect();
+	let list = CraftingList::new(&recipes, &[("ingot", 2)]);
+	let crafts: Vec<_> = list
+		.crafts
+		.iter()
+		.map(|entry| (entry.item_id.as_str(), entry.crafts, entry.amount))
+		.collect();
+	assert_eq!(crafts, vec![("scrap", 1, 2), ("ingot", 2, 2)]);
+	let raw: Vec<_> = list
+		.raw_materials
+		.iter()
+		.map(|ingredient| (ingredient.id.as_str(), ingredient.amount))
+		.collect();
+	assert_eq!(raw, vec![("ore", 4), ("ingot", 1)]);
+}
+
+#[test]
+fn test_leveling_planner() -> Result<()> {
+	use crate::leveling::{ExperienceTable, LevelingPlanner, LevelingRecipe};
+
+	let param_grow = "\
+key,0,1
+#,ExpToNext,ScaledQuestXP
+int32,int32,int16
+0,0,0
+1,300,1
+2,600,1
+3,1100,1
+4,1700,1
+5,2300,1
+";
+	let table = ExperienceTable::from_reader(param_grow.as_bytes())?;
+	assert_eq!(table.exp_to_next(CraftingLevel::unchecked_new(0)), None);
+	assert_eq!(table.exp_to_next(CraftingLevel::unchecked_new(3)), Some(1100));
+	assert_eq!(
+		table.total_exp(CraftingLevel::unchecked_new(1), CraftingLevel::unchecked_new(5)),
+		Some(3700)
+	);
+	assert_eq!(
+		table.total_exp(CraftingLevel::unchecked_new(1), CraftingLevel::unchecked_new(7)),
+		None
+	);
+
+	let builtin = ExperienceTable::builtin();
+	assert_eq!(builtin.exp_to_next(CraftingLevel::unchecked_new(3)), Some(1100));
+	assert_eq!(builtin.exp_to_next(CraftingLevel::unchecked_new(50)), Some(864000));
+	assert_eq!(builtin.exp_to_next(CraftingLevel::max()), None);
+	assert!(builtin
+		.total_exp(CraftingLevel::unchecked_new(1), CraftingLevel::max())
+		.is_some());
+
+	let synthesis: Vec<CraftingActionEnum> = vec![actions::BasicSynthesis.into()];
+	let touch_synthesis: Vec<CraftingActionEnum> =
+		vec![actions::BasicTouch.into(), actions::BasicSynthesis.into()];
+	let recipes = vec![
+		LevelingRecipe {
+			recipe: generate_recipe_lvl(1, 1, 40, 10, 100, 50, 30),
+			base_exp: 100,
+			rotations: vec![synthesis.clone()],
+		},
+		LevelingRecipe {
+			recipe: generate_recipe_lvl(2, 5, 40, 10, 100, 50, 30),
+			base_exp: 400,
+			rotations: vec![synthesis.clone(), touch_synthesis.clone()],
+		},
+	];
+	let plan = LevelingPlanner::builder()
+		.experience_table(table.clone())
+		.crafter_stats(generate_stats(1, 4000, 4000, 500))
+		.job(CraftingJob::Alchemist)
+		.recipes(recipes.clone())
+		.level(CraftingLevel::unchecked_new(1))
+		.target_level(CraftingLevel::unchecked_new(6))
+		.build()
+		.plan()
+		.unwrap();
+
+	assert_eq!(plan.brackets.len(), 2);
+	let first = &plan.brackets[0];
+	assert_eq!(first.recipe.id, "1");
+	assert_eq!((u8::from(first.from_level), u8::from(first.to_level)), (1, 5));
+	let first_exp = first.exp_per_craft;
+	assert_eq!(first.count, (3700 + first_exp - 1) / first_exp);
+
+	// HQ doubles the EXP once Basic Touch is learned
+	let second = &plan.brackets[1];
+	assert_eq!(second.recipe.id, "2");
+	assert_eq!(second.rotation.len(), 2);
+	assert_eq!(second.hq_percent, 100);
+	assert_eq!(second.exp_per_craft, 800);
+	let carried = first.count * first_exp - 3700;
+	assert_eq!(second.count, (2300 - carried + 799) / 800);
+	assert_eq!(plan.total_crafts, first.count + second.count);
+	assert_eq!(plan.level, 6);
+
+	// nothing is known past level 5
+	let too_far = LevelingPlanner::builder()
+		.experience_table(table)
+		.crafter_stats(generate_stats(1, 4000, 4000, 500))
+		.job(CraftingJob::Alchemist)
+		.recipes(recipes)
+		.target_level(CraftingLevel::unchecked_new(7))
+		.build()
+		.plan();
+	assert!(too_far.is_none());
+
+	Ok(())
+}
+
+#[test]
+fn test_gear_stat_calculator() -> Result<()> {
+	use crate::gear::{
+		BaseStatsTable, GearError, GearItem, GearStat, GearStats, Materia, MeldRateTable,
+		StatCalculator,
+	};
+
+	let materia_join_rate = "\
+key,0,1,2,3,4,5,6,7
+#,NQOvermeldPercent[0],NQOvermeldPercent[1],NQOvermeldPercent[2],NQOvermeldPercent[3],HQOvermeldPercent[0],HQOvermeldPercent[1],HQOvermeldPercent[2],HQOvermeldPercent[3]
+int32,byte,byte,byte,byte,byte,byte,byte,byte
+0,90,48,28,16,80,40,20,10
+1,17,10,7,5,0,0,0,0
+";
+	let meld_rates = MeldRateTable::from_reader(materia_join_rate.as_bytes())?;
+	assert_eq!(meld_rates.rate(1, 1, false), Some(48));
+	assert_eq!(meld_rates.rate(1, 0, true), Some(80));
+	assert_eq!(meld_rates.rate(2, 4, false), None);
+
+	let base_stats: BaseStatsTable = [(
+		90,
+		GearStats {
+			craftsmanship: 100,
+			control: 50,
+			cp: 180,
+		},
+	)]
+	.into_iter()
+	.collect();
+	let materia = |stat: GearStat, value: u32, grade: u32| Materia { stat, value, grade };
+	let tool = GearItem {
+		id: 1,
+		name: "Saw".to_string(),
+		hq: false,
+		stats: GearStats {
+			craftsmanship: 3000,
+			control: 1500,
+			cp: 0,
+		},
+		stat_caps: Some(GearStats {
+			craftsmanship: 3100,
+			control: 1560,
+			cp: 10,
+		}),
+		materia_slots: 2,
+		advanced_melding: true,
+		materia: vec![
+			materia(GearStat::Craftsmanship, 36, 2),
+			materia(GearStat::Control, 36, 2),
+			materia(GearStat::Control, 36, 2),
+			materia(GearStat::Cp, 9, 1),
+		],
+	};
+	let ring = GearItem {
+		id: 2,
+		name: "Ring".to_string(),
+		hq: true,
+		stats: GearStats {
+			cp: 40,
+			..Default::default()
+		},
+		stat_caps: None,
+		materia_slots: 1,
+		advanced_melding: false,
+		materia: vec![materia(GearStat::Cp, 11, 1)],
+	};
+	// control is capped at 1560 rather than 1572
+	assert_eq!(
+		tool.total_stats(),
+		GearStats {
+			craftsmanship: 3036,
+			control: 1560,
+			cp: 9,
+		}
+	);
+	assert_eq!(
+		tool.meld_success_rates(&meld_rates),
+		vec![Some(100), Some(100), Some(17), Some(48)]
+	);
+
+	let calculator = |items: Vec<GearItem>, specialist: bool| {
+		StatCalculator::builder()
+			.base_stats(base_stats.clone())
+			.meld_rates(meld_rates.clone())
+			.job(CraftingJob::Carpenter)
+			.level(CraftingLevel::unchecked_new(90))
+			.items(items)
+			.specialist(specialist)
+			.build()
+	};
+	let stats = calculator(vec![tool.clone(), ring.clone()], true).crafter_stats()?;
+	assert_eq!(
+		(stats.craftsmanship, stats.control, stats.cp),
+		(100 + 3036 + 20, 50 + 1560 + 20, 180 + 9 + 51 + 15)
+	);
+	assert_eq!(stats.job_id, 8);
+	assert!(stats.specialist);
+	assert_eq!(stats.levels[CraftingJob::Weaver], 90);
+
+	let expected = calculator(vec![tool.clone(), ring.clone()], false).expected_materia()?;
+	assert_eq!(expected[0].0, 1);
+	assert!((expected[0].1 - (100.0 / 48.0 + 1.0)).abs() < 1e-9);
+	assert!((expected[1].1 - (2.0 + 100.0 / 17.0)).abs() < 1e-9);
+
+	// the ring can't be overmelded
+	let mut overmelded_ring = ring;
+	overmelded_ring.materia.push(materia(GearStat::Cp, 11, 1));
+	assert_eq!(
+		calculator(vec![tool.clone(), overmelded_ring], false).crafter_stats().err(),
+		Some(GearError::InvalidMeld { item_id: 2, slot: 1 })
+	);
+	let low_level = StatCalculator::builder()
+		.base_stats(base_stats)
+		.job(CraftingJob::Carpenter)
+		.level(CraftingLevel::unchecked_new(80))
+		.items(vec![tool.clone()])
+		.build();
+	assert_eq!(
+		low_level.total_stats(),
+		Err(GearError::UnknownLevel(CraftingLevel::unchecked_new(80)))
+	);
+
+	let result = Simulation::builder()
+		.recipe(generate_recipe_lvl(3864, 80, 80, 2000, 1000, 50, 30))
+		.crafter_stats(stats)
+		.actions(vec![actions::BasicSynthesis.into()])
+		.build()
+		.start()
+		.linear(true)
+		.run();
+	assert!(result.simulation.progression > 0);
+
+	// overmelds of a grade missing from the rates are invalid either way
+	let mut unknown_grade = tool.clone();
+	unknown_grade.materia[3].grade = 13;
+	assert_eq!(unknown_grade.meld_success_rates(&meld_rates)[3], None);
+	assert_eq!(unknown_grade.invalid_meld(&meld_rates), Some(3));
+
+	// the built-in tables, with the game's base stats and overmeld rates
+	let builtin = StatCalculator::builder()
+		.job(CraftingJob::Carpenter)
+		.level(CraftingLevel::unchecked_new(90))
+		.items(vec![tool])
+		.build();
+	assert_eq!(
+		builtin.total_stats()?,
+		GearStats {
+			craftsmanship: 3036,
+			control: 1560,
+			cp: 180 + 9,
+		}
+	);
+	let expected = builtin.expected_materia()?;
+	assert!((expected[0].1 - 100.0 / 48.0).abs() < 1e-9);
+	assert!((expected[1].1 - (2.0 + 100.0 / 82.0)).abs() < 1e-9);
+	assert_eq!(MeldRateTable::builtin().rate(12, 1, false), Some(0));
+	assert_eq!(MeldRateTable::builtin().rate(11, 3, true), Some(2));
+
+	Ok(())
+}
+
+#[test]
+fn test_meld_optimizer() {
+	use crate::gear::{
+		BaseStatsTable, GearItem, GearStat, GearStats, Materia, MateriaOption, MeldOptimizer,
+		MeldPlan, MeldRateTable, StatCalculator,
+	};
+
+	let recipe = generate_recipe_rlvl(3864, 90, 560, 80, 100, 3000, 130, 115);
+	let rotation: Vec<CraftingActionEnum> = vec![
+		actions::BasicTouch.into(),
+		actions::BasicTouch.into(),
+		actions::BasicSynthesis.into(),
+	];
+	let base = GearStats {
+		craftsmanship: 4000,
+		control: 3000,
+		cp: 500,
+	};
+	let quality_with_control = |control: u32| {
+		Simulation::builder()
+			.recipe(recipe.clone())
+			.crafter_stats(generate_stats(90, 4000, control, 500))
+			.actions(rotation.clone())
+			.build()
+			.start()
+			.linear(true)
+			.run()
+			.simulation
+			.quality
+	};
+	let target_quality = quality_with_control(3000 + 72);
+	assert!(quality_with_control(3000) < target_quality);
+
+	let mut meld_rates = MeldRateTable::new();
+	meld_rates.insert(1, [90, 48, 28, 16], [90, 48, 28, 16]);
+	meld_rates.insert(2, [17, 10, 7, 5], [17, 10, 7, 5]);
+	let tool = GearItem {
+		id: 1,
+		name: "Saw".to_string(),
+		hq: true,
+		stats: GearStats::default(),
+		stat_caps: Some(GearStats {
+			craftsmanship: 500,
+			control: 60,
+			cp: 20,
+		}),
+		materia_slots: 2,
+		advanced_melding: true,
+		materia: vec![],
+	};
+	let ring = GearItem {
+		id: 2,
+		name: "Ring".to_string(),
+		hq: true,
+		stats: GearStats::default(),
+		stat_caps: None,
+		materia_slots: 1,
+		advanced_melding: false,
+		materia: vec![],
+	};
+	let option = |stat: GearStat, value: u32, grade: u32, cost: u64| MateriaOption {
+		materia: Materia { stat, value, grade },
+		cost,
+	};
+	let materia = vec![
+		option(GearStat::Craftsmanship, 36, 2, 1),
+		option(GearStat::Control, 36, 2, 100),
+		option(GearStat::Control, 12, 1, 45),
+	];
+	let optimize = |items: Vec<GearItem>, target_quality: u32| {
+		let calculator = StatCalculator::builder()
+			.base_stats(BaseStatsTable::from_iter([(90, base)]))
+			.meld_rates(meld_rates.clone())
+			.job(CraftingJob::Alchemist)
+			.level(CraftingLevel::unchecked_new(90))
+			.items(items)
+			.build();
+		MeldOptimizer::builder()
+			.calculator(calculator)
+			.recipe(recipe.clone())
+			.actions(rotation.clone())
+			.materia(materia.clone())
+			.target_quality(target_quality)
+			.build()
+			.solve()
+	};
+	let melds = |plan: &MeldPlan| -> Vec<_> {
+		plan.melds
+			.iter()
+			.map(|meld| (meld.item_id, meld.slot, meld.materia.value, meld.success_rate))
+			.collect()
+	};
+
+	// the saw's control cap leaves the ring's slot needed
+	let plan = optimize(vec![tool.clone(), ring.clone()], quality_with_control(3000 + 72)).unwrap();
+	assert!(plan.quality >= quality_with_control(3000 + 72));
+	assert_eq!(plan.crafter_stats.control, 3000 + 72);
+	assert_eq!(melds(&plan), vec![(1, 0, 36, 100), (2, 0, 36, 100)]);
+	assert!((plan.expected_cost - 200.0).abs() < 1e-9);
+	assert!(optimize(vec![tool.clone()], quality_with_control(3000 + 72)).is_none());
+
+	// overmelding the cheap materia beats a second expensive one
+	let plan = optimize(vec![tool], quality_with_control(3000 + 60)).unwrap();
+	assert_eq!(
+		melds(&plan),
+		vec![(1, 0, 12, 100), (1, 1, 36, 100), (1, 2, 12, 90)]
+	);
+	assert!((plan.expected_cost - (100.0 + 45.0 + 45.0 / 0.9)).abs() < 1e-9);
+	assert_eq!(plan.items[0].materia.len(), 3);
+}
+
+#[test]
+fn test_consumables() {
+	use crate::gear::{Consumable, ConsumableBonus, ConsumableKind, ConsumableTable, GearStat};
+
+	let bonus = |stat: GearStat, percent: u32, max: u32, hq_max: u32| ConsumableBonus {
+		stat,
+		percent,
+		max,
+		hq_percent: percent + 1,
+		hq_max,
+	};
+	let consumable = |id: u32, name: &str, kind: ConsumableKind, bonuses, price: u64| Consumable {
+		id,
+		name: name.to_string(),
+		kind,
+		bonuses,
+		price: Some(price),
+		hq_price: Some(price * 2),
+	};
+	let table = ConsumableTable::new(vec![
+		consumable(
+			1,
+			"Stew",
+			ConsumableKind::Food,
+			vec![
+				bonus(GearStat::Control, 4, 60, 70),
+				bonus(GearStat::Cp, 20, 50, 60),
+			],
+			100,
+		),
+		consumable(
+			2,
+			"Salad",
+			ConsumableKind::Food,
+			vec![bonus(GearStat::Control, 1, 20, 25)],
+			10,
+		),
+		consumable(
+			3,
+			"Draught",
+			ConsumableKind::Medicine,
+			vec![bonus(GearStat::Cp, 6, 20, 21)],
+			30,
+		),
+	]);
+	assert_eq!(table.food().count(), 2);
+	assert_eq!(table.get_by_name("draught").map(|medicine| medicine.id), Some(3));
+
+	let stats = generate_stats(90, 4000, 3000, 500);
+	let fed = stats.with_consumables(table.get(1), table.get(3), false, false);
+	// CP bonuses are both worked out from the stats without food
+	assert_eq!((fed.craftsmanship, fed.control, fed.cp), (4000, 3060, 500 + 50 + 20));
+	let fed = stats.with_consumables(table.get(2), None, true, false);
+	// 2% of 3000 is over the HQ cap
+	assert_eq!((fed.control, fed.cp), (3025, 500));
+	// NQ food with HQ medicine
+	let fed = stats.with_consumables(table.get(1), table.get(3), false, true);
+	assert_eq!((fed.control, fed.cp), (3060, 500 + 50 + 21));
+
+	let recipe = generate_recipe_rlvl(3864, 90, 560, 80, 100, 10000, 130, 115);
+	let rotation: Vec<CraftingActionEnum> =
+		vec![actions::BasicTouch.into(), actions::BasicSynthesis.into()];
+	let rankings = table.rank(&recipe, &stats, &rotation, false, false);
+	assert_eq!(rankings.len(), 3 * 2);
+	let names: Vec<_> = rankings
+		.iter()
+		.map(|ranking| {
+			(
+				ranking.food.as_ref().map(|food| food.name.as_str()),
+				ranking.medicine.as_ref().map(|medicine| medicine.name.as_str()),
+			)
+		})
+		.collect();
+	// medicine doesn't help the quality so it's only worth its price
+	assert_eq!(
+		names,
+		vec![
+			(Some("Stew"), None),
+			(Some("Stew"), Some("Draught")),
+			(Some("Salad"), None),
+			(Some("Salad"), Some("Draught")),
+			(None, None),
+			(None, Some("Draught")),
+		]
+	);
+	assert!(rankings[0].quality > rankings[2].quality);
+	assert!(rankings[2].quality > rankings[4].quality);
+	assert_eq!(rankings[1].price, Some(130));
+
+	// an unpriced consumable ranks after priced ones of the same result, not as free
+	let mut table = table;
+	table.consumables[2].price = None;
+	let rankings = table.rank(&recipe, &stats, &rotation, false, false);
+	let medicines: Vec<_> = rankings[..2]
+		.iter()
+		.map(|ranking| (ranking.medicine.is_some(), ranking.price))
+		.collect();
+	assert_eq!(medicines, vec![(false, Some(100)), (true, None)]);
+}
+
+#[test]
+fn test_consumable_sheets() -> Result<()> {
+	use crate::gear::{ConsumableKind, ConsumableTable, GearStat};
+
+	let item_csv = "\
+#,Name,ItemAction
+36060,Tsai tou Vounou,1
+36116,Cunning Craftsman's Tisane,2
+4650,Boiled Egg,3
+27960,Stew of the Forest,4
+";
+	let item_action_csv = "\
+#,Type,Data[0],Data[1]
+1,844,48,100
+2,846,49,101
+3,844,48,102
+4,844,48,103
+";
+	let item_food_csv = "\
+key,0,1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17
+#,BaseParam[0],IsRelative[0],Value[0],Max[0],Value{HQ}[0],Max{HQ}[0],BaseParam[1],IsRelative[1],Value[1],Max[1],Value{HQ}[1],Max{HQ}[1],BaseParam[2],IsRelative[2],Value[2],Max[2],Value{HQ}[2],Max{HQ}[2]
+int32,BaseParam,bit&01,sbyte,int16,sbyte,int16,BaseParam,bit&01,sbyte,int16,sbyte,int16,BaseParam,bit&01,sbyte,int16,sbyte,int16
+100,11,True,20,70,26,86,71,True,4,78,5,97,0,False,0,0,0,0
+101,70,True,3,75,4,90,0,False,0,0,0,0,0,False,0,0,0,0
+102,3,True,3,15,4,18,0,False,0,0,0,0,0,False,0,0,0,0
+103,70,False,10,0,12,0,0,False,0,0,0,0,0,False,0,0,0,0
+";
+	let table = ConsumableTable::from_readers(
+		item_csv.as_bytes(),
+		item_action_csv.as_bytes(),
+		item_food_csv.as_bytes(),
+	)?;
+	// neither the egg's vitality nor a flat bonus is of any use to a crafter
+	assert_eq!(table.consumables.len(), 2);
+
+	let food = table.get(36060).unwrap();
+	assert_eq!(food.kind, ConsumableKind::Food);
+	assert_eq!(food.name, "Tsai tou Vounou");
+	assert_eq!(food.bonuses[0].stat, GearStat::Cp);
+	assert_eq!((food.bonuses[1].hq_percent, food.bonuses[1].hq_max), (5, 97));
+	let medicine = table.get_by_name("cunning craftsman's tisane").unwrap();
+	assert_eq!(medicine.kind, ConsumableKind::Medicine);
+	assert_eq!(medicine.bonuses[0].stat, GearStat::Craftsmanship);
+
+	let stats = generate_stats(90, 4000, 3000, 500);
+	let fed = stats.with_consumables(Some(food), Some(medicine), true, true);
+	assert_eq!(
+		(fed.craftsmanship, fed.control, fed.cp),
+		(4000 + 90, 3000 + 97, 500 + 86)
+	);
+
+	Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_gearset_import() -> Result<()> {
+	use crate::gear::{
+		BaseStatsTable, Consumable, ConsumableBonus, ConsumableKind, ConsumableTable,
+		GearCatalog, GearItem, GearStat, GearStats, GearsetImportError, GearsetImporter, Materia,
+	};
+
+	let mut catalog = GearCatalog::new();
+	let item = |id: u32, hq: bool, craftsmanship: u32, control: u32, cp: u32| GearItem {
+		id,
+		name: format!("Item {}", id),
+		hq,
+		stats: GearStats {
+			craftsmanship,
+			control,
+			cp,
+		},
+		stat_caps: None,
+		materia_slots: 2,
+		advanced_melding: true,
+		materia: vec![],
+	};
+	catalog.insert_item(item(100, false, 900, 450, 0));
+	catalog.insert_item(item(100, true, 1000, 500, 0));
+	catalog.insert_item(item(200, false, 0, 0, 40));
+	catalog.insert_materia(
+		41763,
+		Materia {
+			stat: GearStat::Control,
+			value: 36,
+			grade: 10,
+		},
+	);
+	catalog.consumables = ConsumableTable::new(vec![Consumable {
+		id: 300,
+		name: "Stew".to_string(),
+		kind: ConsumableKind::Food,
+		bonuses: vec![ConsumableBonus {
+			stat: GearStat::Cp,
+			percent: 10,
+			max: 10,
+			hq_percent: 20,
+			hq_max: 30,
+		}],
+		price: None,
+		hq_price: None,
+	}]);
+	let base_stats = BaseStatsTable::from_iter([
+		(
+			90,
+			GearStats {
+				craftsmanship: 100,
+				control: 50,
+				cp: 180,
+			},
+		),
+		(
+			100,
+			GearStats {
+				craftsmanship: 120,
+				control: 60,
+				cp: 180,
+			},
+		),
+	]);
+	let importer = GearsetImporter::builder()
+		.catalog(catalog)
+		.base_stats(base_stats)
+		.build();
+
+	let teamcraft = r#"[
+		{
+			"name": "Carpenter",
+			"job": 8,
+			"level": 90,
+			"mainHand": { "itemId": 100, "hq": true, "materias": [41763, 41763, 41763, 0, 0] },
+			"ring1": { "itemId": 200, "hq": false, "materias": [0, 0] },
+			"crystal": { "itemId": 4001, "hq": false, "materias": [] },
+			"food": { "itemId": 300, "hq": true }
+		},
+		{ "name": "Paladin", "job": 19, "mainHand": { "itemId": 999, "hq": true } }
+	]"#;
+	let imported = importer.teamcraft(teamcraft)?;
+	assert_eq!(imported.stats.len(), 1);
+	let carpenter = &imported.stats[&CraftingJob::Carpenter];
+	assert_eq!(
+		(carpenter.craftsmanship, carpenter.control, carpenter.cp),
+		(100 + 1000 + 20, 50 + 500 + 3 * 36 + 20, 180 + 40 + 15 + 30)
+	);
+	assert!(carpenter.specialist);
+	assert_eq!(carpenter.job_id, 8);
+	assert_eq!(imported.levels[CraftingJob::Carpenter], 90);
+	assert_eq!(imported.levels[CraftingJob::Weaver], 0);
+
+	// Etro rings are keyed by side, and gearsets without a level are at the cap
+	let etro = r#"{
+		"name": "Weaver",
+		"job": 13,
+		"weapon": 100,
+		"fingerL": 200,
+		"fingerR": 200,
+		"food": 300,
+		"materia": { "100": { "1": 41763 }, "200R": { "1": 41763 } }
+	}"#;
+	let imported = importer.etro(etro)?;
+	let weaver = &imported.stats[&CraftingJob::Weaver];
+	assert_eq!(
+		(weaver.craftsmanship, weaver.control, weaver.cp),
+		(120 + 1000, 60 + 500 + 2 * 36, 180 + 80 + 30)
+	);
+	assert!(!weaver.specialist);
+	assert_eq!(weaver.level, 100);
+	assert_eq!(weaver.levels[CraftingJob::Weaver], 100);
+
+	let unknown = r#"{ "job": 8, "mainHand": { "itemId": 101, "materias": [41764] }, "food": 301 }"#;
+	match importer.teamcraft(unknown) {
+		Err(GearsetImportError::UnknownItems(item_ids)) => {
+			assert_eq!(item_ids, vec![101, 301]);
+		}
+		_ => panic!("expected unknown items"),
+	}
+	let unknown_materia = r#"{ "job": 8, "weapon": 100, "materia": { "100": { "1": 41764 } } }"#;
+	let err = importer.etro(unknown_materia).err().unwrap();
+	assert_eq!(err.to_string(), "unknown item ids: 41764");
+
+	Ok(())
+}
+
 fn generate_recipe_lvl(
 	id: u32,
 	lvl: u8,
@@ -1254,6 +3614,7 @@ fn generate_recipe_lvl(
 	)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn generate_recipe_rlvl(
 	id: u32,
 	lvl: u8,
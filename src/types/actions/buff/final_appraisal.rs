@@ -5,7 +5,7 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct FinalAppraisal;
 
 impl BuffAction for FinalAppraisal {
@@ -27,6 +27,10 @@ impl BuffAction for FinalAppraisal {
 }
 
 impl CraftingAction for FinalAppraisal {
+	fn get_name(&self) -> &'static str {
+		"Final Appraisal"
+	}
+
 	fn skip_on_fail(&self) -> bool {
 		true
 	}
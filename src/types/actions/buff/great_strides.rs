@@ -5,7 +5,7 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct GreatStrides;
 
 impl BuffAction for GreatStrides {
@@ -27,6 +27,10 @@ impl BuffAction for GreatStrides {
 }
 
 impl CraftingAction for GreatStrides {
+	fn get_name(&self) -> &'static str {
+		"Great Strides"
+	}
+
 	fn skip_on_fail(&self) -> bool {
 		true
 	}
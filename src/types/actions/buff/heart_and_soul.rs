@@ -6,7 +6,7 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct HeartAndSoul;
 
 impl BuffAction for HeartAndSoul {
@@ -47,6 +47,10 @@ impl BuffAction for HeartAndSoul {
 }
 
 impl CraftingAction for HeartAndSoul {
+	fn get_name(&self) -> &'static str {
+		"Heart and Soul"
+	}
+
 	fn skip_on_fail(&self) -> bool {
 		true
 	}
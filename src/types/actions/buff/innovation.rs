@@ -5,7 +5,7 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Innovation;
 
 impl BuffAction for Innovation {
@@ -27,6 +27,10 @@ impl BuffAction for Innovation {
 }
 
 impl CraftingAction for Innovation {
+	fn get_name(&self) -> &'static str {
+		"Innovation"
+	}
+
 	fn skip_on_fail(&self) -> bool {
 		true
 	}
@@ -5,7 +5,7 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Manipulation;
 
 impl BuffAction for Manipulation {
@@ -38,6 +38,10 @@ impl BuffAction for Manipulation {
 }
 
 impl CraftingAction for Manipulation {
+	fn get_name(&self) -> &'static str {
+		"Manipulation"
+	}
+
 	fn get_wait_duration(&self) -> u32 {
 		2
 	}
@@ -5,7 +5,7 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct QuickInnovation;
 
 impl BuffAction for QuickInnovation {
@@ -27,6 +27,10 @@ impl BuffAction for QuickInnovation {
 }
 
 impl CraftingAction for QuickInnovation {
+	fn get_name(&self) -> &'static str {
+		"Quick Innovation"
+	}
+
 	fn skip_on_fail(&self) -> bool {
 		true
 	}
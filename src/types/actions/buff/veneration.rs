@@ -5,7 +5,7 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Veneration;
 
 impl BuffAction for Veneration {
@@ -27,6 +27,10 @@ impl BuffAction for Veneration {
 }
 
 impl CraftingAction for Veneration {
+	fn get_name(&self) -> &'static str {
+		"Veneration"
+	}
+
 	fn skip_on_fail(&self) -> bool {
 		true
 	}
@@ -5,7 +5,7 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct WasteNot;
 
 impl BuffAction for WasteNot {
@@ -31,6 +31,10 @@ impl BuffAction for WasteNot {
 }
 
 impl CraftingAction for WasteNot {
+	fn get_name(&self) -> &'static str {
+		"Waste Not"
+	}
+
 	fn skip_on_fail(&self) -> bool {
 		true
 	}
@@ -5,7 +5,7 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct WasteNotII;
 
 impl BuffAction for WasteNotII {
@@ -31,6 +31,10 @@ impl BuffAction for WasteNotII {
 }
 
 impl CraftingAction for WasteNotII {
+	fn get_name(&self) -> &'static str {
+		"Waste Not II"
+	}
+
 	fn skip_on_fail(&self) -> bool {
 		true
 	}
@@ -5,10 +5,14 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct CarefulObservation;
 
 impl CraftingAction for CarefulObservation {
+	fn get_name(&self) -> &'static str {
+		"Careful Observation"
+	}
+
 	fn skip_on_fail(&self) -> bool {
 		true
 	}
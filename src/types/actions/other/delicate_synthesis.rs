@@ -5,7 +5,7 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct DelicateSynthesis;
 
 impl GeneralAction for DelicateSynthesis {
@@ -23,6 +23,10 @@ impl GeneralAction for DelicateSynthesis {
 }
 
 impl CraftingAction for DelicateSynthesis {
+	fn get_name(&self) -> &'static str {
+		"Delicate Synthesis"
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		(CraftingJob::Any, CraftingLevel::unchecked_new(76))
 	}
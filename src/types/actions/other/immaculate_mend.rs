@@ -5,10 +5,14 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct ImmaculateMend;
 
 impl CraftingAction for ImmaculateMend {
+	fn get_name(&self) -> &'static str {
+		"Immaculate Mend"
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		(CraftingJob::Any, CraftingLevel::unchecked_new(98))
 	}
@@ -5,10 +5,14 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct MastersMend;
 
 impl CraftingAction for MastersMend {
+	fn get_name(&self) -> &'static str {
+		"Master's Mend"
+	}
+
 	fn skip_on_fail(&self) -> bool {
 		true
 	}
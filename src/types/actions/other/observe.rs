@@ -5,10 +5,14 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Observe;
 
 impl CraftingAction for Observe {
+	fn get_name(&self) -> &'static str {
+		"Observe"
+	}
+
 	fn skip_on_fail(&self) -> bool {
 		true
 	}
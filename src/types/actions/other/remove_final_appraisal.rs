@@ -5,10 +5,14 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct RemoveFinalAppraisal;
 
 impl CraftingAction for RemoveFinalAppraisal {
+	fn get_name(&self) -> &'static str {
+		"Remove Final Appraisal"
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		(CraftingJob::Any, CraftingLevel::unchecked_new(42))
 	}
@@ -6,7 +6,7 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct TrainedPerfection;
 
 impl BuffAction for TrainedPerfection {
@@ -26,6 +26,10 @@ impl BuffAction for TrainedPerfection {
 }
 
 impl CraftingAction for TrainedPerfection {
+	fn get_name(&self) -> &'static str {
+		"Trained Perfection"
+	}
+
 	fn skip_on_fail(&self) -> bool {
 		true
 	}
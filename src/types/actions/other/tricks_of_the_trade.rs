@@ -5,10 +5,14 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct TricksOfTheTrade;
 
 impl CraftingAction for TricksOfTheTrade {
+	fn get_name(&self) -> &'static str {
+		"Tricks of the Trade"
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		(CraftingJob::Any, CraftingLevel::unchecked_new(13))
 	}
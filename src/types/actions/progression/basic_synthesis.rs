@@ -1,6 +1,6 @@
 use crate::types::{enums::*, structs::CraftingLevel, traits::*, Simulation};
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct BasicSynthesis;
 
 impl ProgressAction for BasicSynthesis {}
@@ -24,6 +24,10 @@ impl GeneralAction for BasicSynthesis {
 }
 
 impl CraftingAction for BasicSynthesis {
+	fn get_name(&self) -> &'static str {
+		"Basic Synthesis"
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		(CraftingJob::Any, CraftingLevel::unchecked_new(1))
 	}
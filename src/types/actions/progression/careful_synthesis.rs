@@ -5,12 +5,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct CarefulSynthesis;
 
 impl ProgressAction for CarefulSynthesis {}
 
 impl CraftingAction for CarefulSynthesis {
+	fn get_name(&self) -> &'static str {
+		"Careful Synthesis"
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		(CraftingJob::Any, CraftingLevel::unchecked_new(62))
 	}
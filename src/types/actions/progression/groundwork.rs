@@ -5,12 +5,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Groundwork;
 
 impl ProgressAction for Groundwork {}
 
 impl CraftingAction for Groundwork {
+	fn get_name(&self) -> &'static str {
+		"Groundwork"
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		(CraftingJob::Any, CraftingLevel::unchecked_new(72))
 	}
@@ -5,12 +5,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct IntensiveSynthesis;
 
 impl ProgressAction for IntensiveSynthesis {}
 
 impl CraftingAction for IntensiveSynthesis {
+	fn get_name(&self) -> &'static str {
+		"Intensive Synthesis"
+	}
+
 	fn requires_good(&self) -> bool {
 		true
 	}
@@ -5,12 +5,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct MuscleMemory;
 
 impl ProgressAction for MuscleMemory {}
 
 impl CraftingAction for MuscleMemory {
+	fn get_name(&self) -> &'static str {
+		"Muscle Memory"
+	}
+
 	fn can_be_moved(&self, current_index: u32) -> bool {
 		current_index > 0
 	}
@@ -5,12 +5,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct PrudentSynthesis;
 
 impl ProgressAction for PrudentSynthesis {}
 
 impl CraftingAction for PrudentSynthesis {
+	fn get_name(&self) -> &'static str {
+		"Prudent Synthesis"
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		(CraftingJob::Any, CraftingLevel::unchecked_new(88))
 	}
@@ -5,12 +5,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct RapidSynthesis;
 
 impl ProgressAction for RapidSynthesis {}
 
 impl CraftingAction for RapidSynthesis {
+	fn get_name(&self) -> &'static str {
+		"Rapid Synthesis"
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		(CraftingJob::Any, CraftingLevel::unchecked_new(9))
 	}
@@ -6,12 +6,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct AdvancedTouch;
 
 impl QualityAction for AdvancedTouch {}
 
 impl CraftingAction for AdvancedTouch {
+	fn get_name(&self) -> &'static str {
+		"Advanced Touch"
+	}
+
 	fn has_combo(&self, simulation_state: &Simulation) -> bool {
 		// need to check not only for StandardTouch, but that it was also combo'd
 		for step in simulation_state.steps.iter().rev() {
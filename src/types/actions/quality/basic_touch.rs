@@ -1,11 +1,15 @@
 use crate::types::{enums::*, structs::CraftingLevel, traits::*, Simulation};
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct BasicTouch;
 
 impl QualityAction for BasicTouch {}
 
 impl CraftingAction for BasicTouch {
+	fn get_name(&self) -> &'static str {
+		"Basic Touch"
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		(CraftingJob::Any, CraftingLevel::unchecked_new(5))
 	}
@@ -5,12 +5,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct ByregotsBlessing;
 
 impl QualityAction for ByregotsBlessing {}
 
 impl CraftingAction for ByregotsBlessing {
+	fn get_name(&self) -> &'static str {
+		"Byregot's Blessing"
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		(CraftingJob::Any, CraftingLevel::unchecked_new(50))
 	}
@@ -5,12 +5,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct DaringTouch;
 
 impl QualityAction for DaringTouch {}
 
 impl CraftingAction for DaringTouch {
+	fn get_name(&self) -> &'static str {
+		"Daring Touch"
+	}
+
 	fn has_combo(&self, simulation_state: &Simulation) -> bool {
 		simulation_state.has_buff(Buff::Expedience)
 	}
@@ -5,12 +5,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct HastyTouch;
 
 impl QualityAction for HastyTouch {}
 
 impl CraftingAction for HastyTouch {
+	fn get_name(&self) -> &'static str {
+		"Hasty Touch"
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		(CraftingJob::Any, CraftingLevel::unchecked_new(9))
 	}
@@ -5,12 +5,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct PreciseTouch;
 
 impl QualityAction for PreciseTouch {}
 
 impl CraftingAction for PreciseTouch {
+	fn get_name(&self) -> &'static str {
+		"Precise Touch"
+	}
+
 	fn requires_good(&self) -> bool {
 		true
 	}
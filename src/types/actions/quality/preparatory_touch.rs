@@ -5,12 +5,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct PreparatoryTouch;
 
 impl QualityAction for PreparatoryTouch {}
 
 impl CraftingAction for PreparatoryTouch {
+	fn get_name(&self) -> &'static str {
+		"Preparatory Touch"
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		(CraftingJob::Any, CraftingLevel::unchecked_new(71))
 	}
@@ -5,12 +5,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct PrudentTouch;
 
 impl QualityAction for PrudentTouch {}
 
 impl CraftingAction for PrudentTouch {
+	fn get_name(&self) -> &'static str {
+		"Prudent Touch"
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		(CraftingJob::Any, CraftingLevel::unchecked_new(66))
 	}
@@ -6,12 +6,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct RefinedTouch;
 
 impl QualityAction for RefinedTouch {}
 
 impl CraftingAction for RefinedTouch {
+	fn get_name(&self) -> &'static str {
+		"Refined Touch"
+	}
+
 	fn has_combo(&self, simulation_state: &Simulation) -> bool {
 		simulation_state.has_combo_available(&actions::BasicTouch.into())
 	}
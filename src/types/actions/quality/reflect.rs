@@ -5,12 +5,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Reflect;
 
 impl QualityAction for Reflect {}
 
 impl CraftingAction for Reflect {
+	fn get_name(&self) -> &'static str {
+		"Reflect"
+	}
+
 	fn can_be_moved(&self, current_index: u32) -> bool {
 		current_index > 0
 	}
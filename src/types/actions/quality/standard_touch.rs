@@ -6,12 +6,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct StandardTouch;
 
 impl QualityAction for StandardTouch {}
 
 impl CraftingAction for StandardTouch {
+	fn get_name(&self) -> &'static str {
+		"Standard Touch"
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		(CraftingJob::Any, CraftingLevel::unchecked_new(18))
 	}
@@ -5,12 +5,16 @@ use crate::types::{
 	Simulation,
 };
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct TrainedFinesse;
 
 impl QualityAction for TrainedFinesse {}
 
 impl CraftingAction for TrainedFinesse {
+	fn get_name(&self) -> &'static str {
+		"Trained Finesse"
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		(CraftingJob::Any, CraftingLevel::unchecked_new(90))
 	}
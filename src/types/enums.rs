@@ -3,7 +3,8 @@ use num_derive::FromPrimitive;
 
 use crate::types::actions::*;
 
-#[derive(Clone, Copy, Debug, Eq, FromPrimitive, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, FromPrimitive, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StepState {
 	None, // Fails the step
 	Normal,
@@ -33,8 +34,8 @@ pub enum ActionType {
 	Other,
 }
 
-#[derive(Clone, Copy, PartialEq)]
 #[enum_dispatch]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum CraftingActionEnum {
 	// Progress actions
 	BasicSynthesis,
@@ -86,7 +87,113 @@ pub enum CraftingActionEnum {
 	RemoveFinalAppraisal,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+impl CraftingActionEnum {
+	/// Every crafting action, in declaration order.
+	pub const ALL: [CraftingActionEnum; 37] = [
+		CraftingActionEnum::BasicSynthesis(BasicSynthesis),
+		CraftingActionEnum::CarefulSynthesis(CarefulSynthesis),
+		CraftingActionEnum::PrudentSynthesis(PrudentSynthesis),
+		CraftingActionEnum::RapidSynthesis(RapidSynthesis),
+		CraftingActionEnum::Groundwork(Groundwork),
+		CraftingActionEnum::MuscleMemory(MuscleMemory),
+		CraftingActionEnum::IntensiveSynthesis(IntensiveSynthesis),
+		CraftingActionEnum::BasicTouch(BasicTouch),
+		CraftingActionEnum::StandardTouch(StandardTouch),
+		CraftingActionEnum::AdvancedTouch(AdvancedTouch),
+		CraftingActionEnum::HastyTouch(HastyTouch),
+		CraftingActionEnum::ByregotsBlessing(ByregotsBlessing),
+		CraftingActionEnum::PreciseTouch(PreciseTouch),
+		CraftingActionEnum::PrudentTouch(PrudentTouch),
+		CraftingActionEnum::TrainedEye(TrainedEye),
+		CraftingActionEnum::PreparatoryTouch(PreparatoryTouch),
+		CraftingActionEnum::Reflect(Reflect),
+		CraftingActionEnum::TrainedFinesse(TrainedFinesse),
+		CraftingActionEnum::DaringTouch(DaringTouch),
+		CraftingActionEnum::RefinedTouch(RefinedTouch),
+		CraftingActionEnum::TricksOfTheTrade(TricksOfTheTrade),
+		CraftingActionEnum::MastersMend(MastersMend),
+		CraftingActionEnum::Manipulation(Manipulation),
+		CraftingActionEnum::ImmaculateMend(ImmaculateMend),
+		CraftingActionEnum::WasteNot(WasteNot),
+		CraftingActionEnum::WasteNotII(WasteNotII),
+		CraftingActionEnum::GreatStrides(GreatStrides),
+		CraftingActionEnum::Innovation(Innovation),
+		CraftingActionEnum::Veneration(Veneration),
+		CraftingActionEnum::FinalAppraisal(FinalAppraisal),
+		CraftingActionEnum::QuickInnovation(QuickInnovation),
+		CraftingActionEnum::TrainedPerfection(TrainedPerfection),
+		CraftingActionEnum::Observe(Observe),
+		CraftingActionEnum::HeartAndSoul(HeartAndSoul),
+		CraftingActionEnum::CarefulObservation(CarefulObservation),
+		CraftingActionEnum::DelicateSynthesis(DelicateSynthesis),
+		CraftingActionEnum::RemoveFinalAppraisal(RemoveFinalAppraisal),
+	];
+
+	/// A stable identifier for the action, matching its variant name.
+	pub fn id(&self) -> &'static str {
+		match self {
+			CraftingActionEnum::BasicSynthesis(_) => "BasicSynthesis",
+			CraftingActionEnum::CarefulSynthesis(_) => "CarefulSynthesis",
+			CraftingActionEnum::PrudentSynthesis(_) => "PrudentSynthesis",
+			CraftingActionEnum::RapidSynthesis(_) => "RapidSynthesis",
+			CraftingActionEnum::Groundwork(_) => "Groundwork",
+			CraftingActionEnum::MuscleMemory(_) => "MuscleMemory",
+			CraftingActionEnum::IntensiveSynthesis(_) => "IntensiveSynthesis",
+			CraftingActionEnum::BasicTouch(_) => "BasicTouch",
+			CraftingActionEnum::StandardTouch(_) => "StandardTouch",
+			CraftingActionEnum::AdvancedTouch(_) => "AdvancedTouch",
+			CraftingActionEnum::HastyTouch(_) => "HastyTouch",
+			CraftingActionEnum::ByregotsBlessing(_) => "ByregotsBlessing",
+			CraftingActionEnum::PreciseTouch(_) => "PreciseTouch",
+			CraftingActionEnum::PrudentTouch(_) => "PrudentTouch",
+			CraftingActionEnum::TrainedEye(_) => "TrainedEye",
+			CraftingActionEnum::PreparatoryTouch(_) => "PreparatoryTouch",
+			CraftingActionEnum::Reflect(_) => "Reflect",
+			CraftingActionEnum::TrainedFinesse(_) => "TrainedFinesse",
+			CraftingActionEnum::DaringTouch(_) => "DaringTouch",
+			CraftingActionEnum::RefinedTouch(_) => "RefinedTouch",
+			CraftingActionEnum::TricksOfTheTrade(_) => "TricksOfTheTrade",
+			CraftingActionEnum::MastersMend(_) => "MastersMend",
+			CraftingActionEnum::Manipulation(_) => "Manipulation",
+			CraftingActionEnum::ImmaculateMend(_) => "ImmaculateMend",
+			CraftingActionEnum::WasteNot(_) => "WasteNot",
+			CraftingActionEnum::WasteNotII(_) => "WasteNotII",
+			CraftingActionEnum::GreatStrides(_) => "GreatStrides",
+			CraftingActionEnum::Innovation(_) => "Innovation",
+			CraftingActionEnum::Veneration(_) => "Veneration",
+			CraftingActionEnum::FinalAppraisal(_) => "FinalAppraisal",
+			CraftingActionEnum::QuickInnovation(_) => "QuickInnovation",
+			CraftingActionEnum::TrainedPerfection(_) => "TrainedPerfection",
+			CraftingActionEnum::Observe(_) => "Observe",
+			CraftingActionEnum::HeartAndSoul(_) => "HeartAndSoul",
+			CraftingActionEnum::CarefulObservation(_) => "CarefulObservation",
+			CraftingActionEnum::DelicateSynthesis(_) => "DelicateSynthesis",
+			CraftingActionEnum::RemoveFinalAppraisal(_) => "RemoveFinalAppraisal",
+		}
+	}
+
+	pub fn from_id(id: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|action| action.id() == id)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for CraftingActionEnum {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(self.id())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CraftingActionEnum {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let id = String::deserialize(deserializer)?;
+		Self::from_id(&id)
+			.ok_or_else(|| serde::de::Error::custom(format!("unknown crafting action \"{}\"", id)))
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Buff {
 	InnerQuiet,
 
@@ -114,7 +221,8 @@ pub enum Buff {
 	TrainedPerfection,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CraftingJob {
 	Any,
 	Carpenter,
@@ -127,7 +235,37 @@ pub enum CraftingJob {
 	Culinarian,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+impl CraftingJob {
+	pub const ALL: [CraftingJob; 8] = [
+		CraftingJob::Carpenter,
+		CraftingJob::Blacksmith,
+		CraftingJob::Armorer,
+		CraftingJob::Goldsmith,
+		CraftingJob::Leatherworker,
+		CraftingJob::Weaver,
+		CraftingJob::Alchemist,
+		CraftingJob::Culinarian,
+	];
+
+	/// The job with the given ClassJob id (8 for Carpenter through 15 for Culinarian),
+	/// as used by [`Craft::job`](super::structs::Craft::job); anything else is `Any`.
+	pub fn from_class_job_id(class_job_id: u32) -> Self {
+		match class_job_id {
+			8..=15 => Self::ALL[(class_job_id - 8) as usize],
+			_ => Self::Any,
+		}
+	}
+
+	pub fn class_job_id(&self) -> Option<u32> {
+		Self::ALL
+			.iter()
+			.position(|job| job == self)
+			.map(|ix| ix as u32 + 8)
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FailCause {
 	UnsafeAction,
 	DurabilityReachedZero,
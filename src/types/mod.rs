@@ -1,6 +1,9 @@
 mod simulation;
 pub use simulation::*;
 
+mod state_key;
+pub(crate) use state_key::*;
+
 pub mod actions;
 pub mod enums;
 pub mod structs;
@@ -1,16 +1,17 @@
 use bon::bon;
 use num_traits::FromPrimitive;
-use rand::{random, Rng};
-use std::collections::{HashMap, HashSet};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashSet;
 
 use crate::types::{
 	actions,
 	enums::{Buff, CraftingActionEnum, FailCause, StepState},
 	structs::*,
 	tables,
-	traits::CraftingAction,
+	traits::{CraftingAction, SimulationRng},
 };
 
+#[derive(Clone)]
 pub struct Simulation {
 	pub recipe: Craft,
 	pub crafter_stats: CrafterStats,
@@ -44,6 +45,11 @@ pub struct Simulation {
 	pub safe: bool,
 
 	possible_conditions: HashSet<StepState>,
+
+	// success rolls and condition ticks draw from separate streams, so that
+	// changing one action doesn't reshuffle every following condition
+	success_rng: Box<dyn SimulationRng>,
+	condition_rng: Box<dyn SimulationRng>,
 }
 
 #[bon]
@@ -57,19 +63,13 @@ impl Simulation {
 		hq_ingredients: Option<Vec<Ingredient>>,
 		step_states: Option<Vec<StepState>>,
 		fails: Option<Vec<usize>>,
+		seed: Option<u64>,
+		success_rng: Option<Box<dyn SimulationRng>>,
+		condition_rng: Option<Box<dyn SimulationRng>>,
 	) -> Self {
-		let mut starting_quality = 0;
-		if let Some(hq_ingredients) = &hq_ingredients {
-			for hq_ingredient in hq_ingredients {
-				if let Some(ingredient) = recipe
-					.ingredients
-					.iter()
-					.find(|&recipe_ingredient| recipe_ingredient.id == hq_ingredient.id)
-				{
-					starting_quality += ingredient.quality.unwrap_or(0) * hq_ingredient.amount;
-				}
-			}
-		}
+		let starting_quality = hq_ingredients
+			.as_ref()
+			.map_or(0, |hq_ingredients| recipe.initial_quality(hq_ingredients));
 
 		let binary_string = format!("{:b}", recipe.conditions_flag);
 		let possible_conditions = binary_string
@@ -88,6 +88,14 @@ impl Simulation {
 		let durability = recipe.durability as i32;
 		let max_cp = crafter_stats.cp;
 
+		let (seeded_success_rng, seeded_condition_rng) = match seed {
+			Some(seed) => Self::seeded_rngs(seed),
+			None => (
+				Box::new(StdRng::from_entropy()) as Box<dyn SimulationRng>,
+				Box::new(StdRng::from_entropy()) as Box<dyn SimulationRng>,
+			),
+		};
+
 		Self {
 			recipe,
 			crafter_stats,
@@ -107,9 +115,24 @@ impl Simulation {
 			last_possible_reclaim_step: None,
 			safe: false,
 			possible_conditions,
+			success_rng: success_rng.unwrap_or(seeded_success_rng),
+			condition_rng: condition_rng.unwrap_or(seeded_condition_rng),
 		}
 	}
 
+	fn seeded_rngs(seed: u64) -> (Box<dyn SimulationRng>, Box<dyn SimulationRng>) {
+		let mut seeder = StdRng::seed_from_u64(seed);
+		let success_rng = StdRng::from_rng(&mut seeder).unwrap();
+		let condition_rng = StdRng::from_rng(&mut seeder).unwrap();
+		(Box::new(success_rng), Box::new(condition_rng))
+	}
+
+	/// Replaces both random streams with ones derived from `seed`,
+	/// exactly as if the simulation had been built with `.seed(seed)`.
+	pub fn reseed(&mut self, seed: u64) {
+		(self.success_rng, self.condition_rng) = Self::seeded_rngs(seed);
+	}
+
 	pub fn state(&self) -> StepState {
 		self.state
 	}
@@ -178,76 +201,101 @@ impl Simulation {
 			.iter()
 			.enumerate()
 			.for_each(|(i, action)| {
-				self.state = self.step_states.get(i).map_or_else(
-					|| StepState::Normal,
-					|&s| {
-						if s == StepState::None {
-							StepState::Normal
-						} else {
-							s
-						}
-					},
-				);
-				let mut fail_cause: Option<FailCause> = None;
-
-				let can_use_action = action.can_be_used_with_flags(&self, Some(linear), Some(safe));
-				if !can_use_action {
-					fail_cause = action.get_fail_cause_with_flags(&self, Some(linear), Some(safe));
-				}
-				let has_enough_cp = action.get_base_cp_cost(&self) <= self.available_cp;
-				if !has_enough_cp {
-					fail_cause = Some(FailCause::NotEnoughCP);
-				}
-				// we can use the action
-				let mut result = if self.success.is_none()
-					&& has_enough_cp
-					&& self.steps.len() < max_steps
-					&& can_use_action
-				{
-					self.run_action_with_flags(action, linear, safe, i)
-				} else {
-					ActionResult {
-						action: *action,
-						success: None,
-						fail_cause,
-						added_progression: 0,
-						added_quality: 0,
-						cp_difference: 0,
-						solidity_difference: 0,
-						skipped: true,
-						combo: None,
-						state: self.state,
-						after_buff_tick: None,
-					}
+				self.state = match self.step_states.get(i) {
+					Some(StepState::None) => StepState::Normal,
+					Some(&s) => s,
+					// conditions only evolve on their own in non-linear runs
+					None if linear => StepState::Normal,
+					None => self.state,
 				};
 
-				if self.steps.len() < max_steps {
-					let quality_before = self.quality;
-					let progression_before = self.progression;
-					let durability_before = self.durability;
-					let cp_before = self.available_cp as i32;
-					let skip_ticks_on_fail =
-						!result.success.unwrap_or(false) && action.skip_on_fail();
-					if self.success.is_none() && !action.skips_buff_ticks() && !skip_ticks_on_fail {
-						self.tick_buffs(action);
-					}
-					result.after_buff_tick = Some(BuffTickResult {
-						added_progression: self.progression - progression_before,
-						added_quality: self.quality - quality_before,
-						cp_difference: self.available_cp as i32 - cp_before,
-						solidity_difference: self.durability - durability_before,
-					});
-				}
+				let result = self.run_step(action, i, linear, safe, max_steps, None);
 
-				if !linear
-					&& *action != actions::FinalAppraisal.into()
-					&& *action != actions::RemoveFinalAppraisal.into()
-				{
+				if !linear && Self::ticks_state(action) {
 					self.tick_state();
 				}
 				self.steps.push(result);
 			});
 
+		self.into_result()
+	}
+
+	/// Runs a single step of the rotation in the current condition, including buff ticks,
+	/// without advancing the condition or recording the step.
+	///
+	/// `forced_roll` replaces the random success roll, where `0` always succeeds
+	/// and `999` always fails.
+	pub(crate) fn run_step(
+		&mut self,
+		action: &CraftingActionEnum,
+		index: usize,
+		linear: bool,
+		safe: bool,
+		max_steps: usize,
+		forced_roll: Option<u32>,
+	) -> ActionResult {
+		let mut fail_cause: Option<FailCause> = None;
+
+		let can_use_action = action.can_be_used_with_flags(self, Some(linear), Some(safe));
+		if !can_use_action {
+			fail_cause = action.get_fail_cause_with_flags(self, Some(linear), Some(safe));
+		}
+		let has_enough_cp = action.get_base_cp_cost(self) <= self.available_cp;
+		if !has_enough_cp {
+			fail_cause = Some(FailCause::NotEnoughCP);
+		}
+		// we can use the action
+		let mut result = if self.success.is_none()
+			&& has_enough_cp
+			&& self.steps.len() < max_steps
+			&& can_use_action
+		{
+			match forced_roll {
+				Some(roll) => self.run_action_with_roll(action, linear, safe, roll),
+				None => self.run_action_with_flags(action, linear, safe, index),
+			}
+		} else {
+			ActionResult {
+				action: *action,
+				success: None,
+				fail_cause,
+				added_progression: 0,
+				added_quality: 0,
+				cp_difference: 0,
+				solidity_difference: 0,
+				skipped: true,
+				combo: None,
+				state: self.state,
+				after_buff_tick: None,
+			}
+		};
+
+		if self.steps.len() < max_steps {
+			let quality_before = self.quality;
+			let progression_before = self.progression;
+			let durability_before = self.durability;
+			let cp_before = self.available_cp as i32;
+			let skip_ticks_on_fail = !result.success.unwrap_or(false) && action.skip_on_fail();
+			if self.success.is_none() && !action.skips_buff_ticks() && !skip_ticks_on_fail {
+				self.tick_buffs(action);
+			}
+			result.after_buff_tick = Some(BuffTickResult {
+				added_progression: self.progression - progression_before,
+				added_quality: self.quality - quality_before,
+				cp_difference: self.available_cp as i32 - cp_before,
+				solidity_difference: self.durability - durability_before,
+			});
+		}
+
+		result
+	}
+
+	/// Whether the condition changes after using the given action.
+	pub fn ticks_state(action: &CraftingActionEnum) -> bool {
+		*action != actions::FinalAppraisal.into() && *action != actions::RemoveFinalAppraisal.into()
+	}
+
+	pub(crate) fn into_result(self) -> SimulationResult {
 		let failed_action = self
 			.steps
 			.iter()
@@ -260,10 +308,17 @@ impl Simulation {
 			} else {
 				true
 			};
+		let collectability = self.get_collectability();
 		let mut res = SimulationResult {
 			steps: self.steps.clone(),
 			hq_percent: self.get_hq_percent(),
 			success,
+			collectability,
+			collectable_tier: if success {
+				self.recipe.collectable_tier(collectability)
+			} else {
+				None
+			},
 			simulation: self,
 			fail_cause: if has_required_quality && !success {
 				Some(FailCause::QualityTooLow)
@@ -304,8 +359,18 @@ impl Simulation {
 		} else if linear {
 			0
 		} else {
-			rand::thread_rng().gen_range(0..100)
+			self.success_rng.gen_range(0..100)
 		};
+		self.run_action_with_roll(action, linear, safe, probability_roll)
+	}
+
+	fn run_action_with_roll(
+		&mut self,
+		action: &CraftingActionEnum,
+		linear: bool,
+		safe: bool,
+		probability_roll: u32,
+	) -> ActionResult {
 		let quality_before = self.quality;
 		let progression_before = self.progression;
 		let durability_before = self.durability;
@@ -364,6 +429,10 @@ impl Simulation {
 		self.buffs.iter().any(|x| x.buff == buff)
 	}
 
+	pub fn buffs(&self) -> &[EffectiveBuff] {
+		&self.buffs
+	}
+
 	pub fn get_buff(&self, buff: Buff) -> Option<&EffectiveBuff> {
 		self.buffs.iter().find(|x| x.buff == buff)
 	}
@@ -399,6 +468,11 @@ impl Simulation {
 		}
 	}
 
+	/// Collectability is a tenth of the quality, which can't go past the recipe's.
+	pub fn get_collectability(&self) -> u32 {
+		self.quality.min(self.recipe.quality) / 10
+	}
+
 	fn tick_buffs(&mut self, action: &CraftingActionEnum) {
 		let buff_vec = self.buffs.clone();
 		buff_vec.iter().for_each(|b| {
@@ -426,15 +500,20 @@ impl Simulation {
 	}
 
 	pub fn tick_state(&mut self) {
+		let states_and_rates = self.next_state_probabilities();
+		self.state = Self::get_weighted_random(&mut *self.condition_rng, states_and_rates)
+			.unwrap_or(StepState::Normal);
+	}
+
+	/// The conditions that can follow the current one, with their probabilities.
+	pub fn next_state_probabilities(&self) -> Vec<(StepState, f64)> {
 		// if current state is EXCELLENT, next is always POOR
 		if self.state == StepState::Excellent {
-			self.state = StepState::Poor;
-			return;
+			return vec![(StepState::Poor, 1.0)];
 		}
 		// if current state is GOOD OMEN, next is always GOOD
 		else if self.state == StepState::GoodOmen {
-			self.state = StepState::Good;
-			return;
+			return vec![(StepState::Good, 1.0)];
 		}
 
 		// Quality Assurance trait, level 63
@@ -444,8 +523,12 @@ impl Simulation {
 			0.2
 		};
 
-		let mut states_and_rates: HashMap<_, _> =
-			HashMap::from_iter(self.possible_conditions.iter().filter_map(|&step_state| {
+		// sorted so that a seeded condition stream always maps to the same states
+		let mut possible_conditions: Vec<_> = self.possible_conditions.iter().copied().collect();
+		possible_conditions.sort();
+		let mut states_and_rates: Vec<_> = possible_conditions
+			.into_iter()
+			.filter_map(|step_state| {
 				match step_state {
 					StepState::Good => Some(if self.recipe.expert.is_some_and(|b| b) {
 						0.12
@@ -467,15 +550,19 @@ impl Simulation {
 					_ => None,
 				}
 				.map(|rate| (step_state, rate))
-			}));
-		let non_normal_rate: f64 = states_and_rates.values().sum();
-		states_and_rates.insert(StepState::Normal, 1.0 - non_normal_rate);
-		self.state = Self::get_weighted_random(states_and_rates).unwrap_or(StepState::Normal);
+			})
+			.collect();
+		let non_normal_rate: f64 = states_and_rates.iter().map(|(_, rate)| rate).sum();
+		states_and_rates.insert(0, (StepState::Normal, 1.0 - non_normal_rate));
+		states_and_rates
 	}
 
-	fn get_weighted_random<T>(weighted_items: HashMap<T, f64>) -> Option<T> {
-		let total_weight: f64 = weighted_items.values().sum();
-		let threshold = random::<f64>() * total_weight;
+	fn get_weighted_random<T>(
+		rng: &mut dyn SimulationRng,
+		weighted_items: Vec<(T, f64)>,
+	) -> Option<T> {
+		let total_weight: f64 = weighted_items.iter().map(|(_, weight)| weight).sum();
+		let threshold = rng.gen::<f64>() * total_weight;
 
 		let mut sum = 0.0;
 		for (item, weight) in weighted_items {
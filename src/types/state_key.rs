@@ -0,0 +1,64 @@
+use super::{
+	actions,
+	enums::{Buff, CraftingActionEnum},
+	traits::CraftingAction,
+	Simulation,
+};
+
+/// The parts of a craft in progress that decide what can still be done with it.
+#[derive(Eq, Hash, PartialEq)]
+pub(crate) struct StateKey {
+	progression: u32,
+	quality: u32,
+	durability: i32,
+	available_cp: u32,
+	buffs: Vec<(Buff, i32, u32)>,
+	// combos only look at the most recent step that wasn't skipped
+	last_step: Option<(CraftingActionEnum, Option<bool>, Option<bool>)>,
+	// Muscle Memory, Reflect and Trained Eye can only open the craft
+	first_step: bool,
+	opener_available: bool,
+	heart_and_soul_used: bool,
+	trained_perfection_used: bool,
+}
+
+impl From<&Simulation> for StateKey {
+	fn from(sim: &Simulation) -> Self {
+		Self {
+			progression: sim.progression,
+			// quality past the maximum can't make a difference
+			quality: sim.quality.min(sim.recipe.quality),
+			durability: sim.durability,
+			available_cp: sim.available_cp,
+			// buffs lasting the whole craft count down from i32::MAX, so how long they've
+			// been up says nothing about the steps left
+			buffs: sim
+				.buffs()
+				.iter()
+				.map(|b| {
+					let duration = if b.duration > i32::MAX / 2 {
+						i32::MAX
+					} else {
+						b.duration
+					};
+					(b.buff, duration, b.stacks)
+				})
+				.collect(),
+			last_step: sim
+				.steps
+				.iter()
+				.rfind(|step| !step.skipped)
+				.map(|step| (step.action, step.success, step.combo)),
+			first_step: sim.steps.is_empty(),
+			opener_available: sim.steps.iter().all(|step| step.action.skips_buff_ticks()),
+			heart_and_soul_used: sim
+				.steps
+				.iter()
+				.any(|step| step.action == actions::HeartAndSoul.into()),
+			trained_perfection_used: sim
+				.steps
+				.iter()
+				.any(|step| step.action == actions::TrainedPerfection.into()),
+		}
+	}
+}
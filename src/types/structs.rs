@@ -1,7 +1,14 @@
 use std::ops::{Index, IndexMut};
-use super::{enums::*, Simulation};
+use bon::bon;
+
+use super::{
+	enums::*,
+	tables::{RecipeLevelError, RecipeLevelTable},
+	Simulation,
+};
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ActionResult {
 	pub action: CraftingActionEnum,
 	pub success: Option<bool>,
@@ -17,6 +24,7 @@ pub struct ActionResult {
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BuffTickResult {
 	pub added_progression: u32,
 	pub added_quality: u32,
@@ -25,6 +33,7 @@ pub struct BuffTickResult {
 }
 
 #[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Craft {
 	pub id: String,
 	pub job: u32,
@@ -50,9 +59,155 @@ pub struct Craft {
 	pub progress_modifier: Option<f64>,
 	pub quality_modifier: Option<f64>,
 	pub required_quality: Option<u32>,
+	/// Percentage of `quality` reached by starting with every ingredient in HQ.
+	pub material_quality_factor: Option<u32>,
+	/// Collectability thresholds of a collectable recipe, lowest first.
+	pub collectable_tiers: Option<Vec<CollectableTier>>,
+}
+
+/// A collectability threshold of a turn-in (scrips, custom deliveries, Ishgard
+/// restoration, ...) and what reaching it is worth.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CollectableTier {
+	/// The minimum collectability, i.e. quality / 10.
+	pub collectability: u32,
+	/// Scrips, points or currency awarded.
+	pub reward: u32,
+	pub exp: Option<u32>,
+}
+
+#[bon]
+impl Craft {
+	/// Builds a recipe from its level, filling the level, dividers, modifiers and
+	/// conditions from the recipe level table (the built-in one unless `table` is given).
+	///
+	/// Fails if the rlvl isn't in the table, or if `stars` is given and doesn't match the
+	/// row's stars.
+	#[builder(finish_fn = build)]
+	pub fn from_recipe_level(
+		rlvl: u32,
+		stars: Option<u32>,
+		durability: u32,
+		progress: u32,
+		quality: u32,
+		table: Option<&RecipeLevelTable>,
+	) -> Result<Self, RecipeLevelError> {
+		let table = table.unwrap_or_else(|| RecipeLevelTable::builtin());
+		let row = table
+			.get(rlvl)
+			.ok_or(RecipeLevelError::UnknownRecipeLevel(rlvl))?;
+
+		let stars = stars.unwrap_or(row.stars);
+		let recipe = Self {
+			rlvl,
+			lvl: row.class_job_level,
+			stars: (stars > 0).then_some(stars),
+			durability,
+			progress,
+			quality,
+			progress_divider: row.progress_divider,
+			quality_divider: row.quality_divider,
+			progress_modifier: Some(row.progress_modifier as f64),
+			quality_modifier: Some(row.quality_modifier as f64),
+			conditions_flag: row.conditions_flag,
+			..Default::default()
+		};
+		table.check(&recipe)?;
+		Ok(recipe)
+	}
+
+	/// The most quality HQ ingredients can give, i.e. with every ingredient in HQ.
+	pub fn max_initial_quality(&self) -> u32 {
+		self.initial_quality(&self.ingredients)
+	}
+
+	/// The quality the craft starts at when using the given HQ ingredients, the rest
+	/// being NQ.
+	///
+	/// As in game, every ingredient with an item level weighs in proportion to it, and all
+	/// of them in HQ give `material_quality_factor` percent of the recipe's quality. When
+	/// that data is missing, each HQ ingredient adds its flat `quality` instead.
+	pub fn initial_quality(&self, hq_ingredients: &[Ingredient]) -> u32 {
+		let hq_amount = |ingredient: &Ingredient| {
+			hq_ingredients
+				.iter()
+				.filter(|hq_ingredient| hq_ingredient.id == ingredient.id)
+				.map(|hq_ingredient| hq_ingredient.amount)
+				.sum::<u32>()
+				.min(ingredient.amount)
+		};
+
+		match self.material_quality_factor {
+			Some(material_quality_factor) if self.uses_item_levels() => {
+				let (hq_ilvl, total_ilvl) = self
+					.ingredients
+					.iter()
+					.filter_map(|ingredient| {
+						ingredient.ilvl.map(|ilvl| {
+							(
+								ilvl as u64 * hq_amount(ingredient) as u64,
+								ilvl as u64 * ingredient.amount as u64,
+							)
+						})
+					})
+					.fold((0, 0), |(hq, total), (ingredient_hq, ingredient_total)| {
+						(hq + ingredient_hq, total + ingredient_total)
+					});
+				if total_ilvl == 0 {
+					return 0;
+				}
+				(self.quality as u64 * material_quality_factor as u64 * hq_ilvl
+					/ (100 * total_ilvl)) as u32
+			}
+			_ => {
+				let quality: u32 = self
+					.ingredients
+					.iter()
+					.map(|ingredient| ingredient.quality.unwrap_or(0) * hq_amount(ingredient))
+					.sum();
+				match self.material_quality_factor {
+					Some(material_quality_factor) => {
+						quality.min(self.quality * material_quality_factor / 100)
+					}
+					None => quality,
+				}
+			}
+		}
+	}
+
+	/// How much one HQ item of the ingredient counts towards [`Craft::initial_quality`],
+	/// relative to the other ingredients.
+	pub(crate) fn hq_weight(&self, ingredient: &Ingredient) -> u32 {
+		if self.material_quality_factor.is_some() && self.uses_item_levels() {
+			ingredient.ilvl.unwrap_or(0)
+		} else {
+			ingredient.quality.unwrap_or(0)
+		}
+	}
+
+	fn uses_item_levels(&self) -> bool {
+		self.ingredients
+			.iter()
+			.any(|ingredient| ingredient.ilvl.is_some())
+	}
+
+	/// Index into `collectable_tiers` of the best tier reached with this collectability.
+	pub fn collectable_tier(&self, collectability: u32) -> Option<usize> {
+		self.collectable_tiers
+			.as_ref()?
+			.iter()
+			.rposition(|tier| collectability >= tier.collectability)
+	}
+
+	/// Checks the level-derived fields against the built-in recipe level table.
+	pub fn check_recipe_level(&self) -> Result<(), RecipeLevelError> {
+		RecipeLevelTable::builtin().check(self)
+	}
 }
 
 #[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CrafterStats {
 	pub job_id: u32,
 	pub craftsmanship: u32,
@@ -64,7 +219,18 @@ pub struct CrafterStats {
 	pub levels: CrafterLevels,
 }
 
+impl CrafterStats {
+	/// The crafter's level in the given job, or their current level for `Any`.
+	pub fn level_for(&self, job: CraftingJob) -> CraftingLevel {
+		match job {
+			CraftingJob::Any => self.level,
+			job => self.levels[job],
+		}
+	}
+}
+
 #[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CrafterLevels {
 	crp: CraftingLevel,
 	bsm: CraftingLevel,
@@ -148,7 +314,12 @@ impl IndexMut<CraftingJob> for CrafterLevels {
 
 const MAX_LEVEL: u8 = 100;
 
-#[derive(Clone, Copy, Default, Eq, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, PartialOrd)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(try_from = "u8", into = "u8")
+)]
 pub struct CraftingLevel {
 	val: u8,
 }
@@ -173,7 +344,7 @@ impl TryFrom<u8> for CraftingLevel {
 	type Error = &'static str;
 
 	fn try_from(value: u8) -> Result<Self, Self::Error> {
-		CraftingLevel::new(value).ok_or("")
+		CraftingLevel::new(value).ok_or("crafting level must be between 0 and 100")
 	}
 }
 impl From<CraftingLevel> for u8 {
@@ -223,10 +394,15 @@ impl EffectiveBuff {
 }
 
 #[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ingredient {
 	pub id: String,
 	pub amount: u32,
+	/// Quality added by each HQ item, used when the recipe lacks the data for
+	/// [`Craft::initial_quality`]'s item level weighting.
 	pub quality: Option<u32>,
+	/// Item level, for ingredients that can be HQ.
+	pub ilvl: Option<u32>,
 	pub step_id: Option<String>,
 	pub part: Option<String>,
 	pub phase: Option<u32>,
@@ -237,6 +413,46 @@ pub struct SimulationResult {
 	pub steps: Vec<ActionResult>,
 	pub hq_percent: u32,
 	pub success: bool,
+	/// Quality / 10, as shown for collectables.
+	pub collectability: u32,
+	/// Index into the recipe's `collectable_tiers` of the tier reached, if the craft
+	/// succeeded and reached any.
+	pub collectable_tier: Option<usize>,
 	pub simulation: Simulation,
 	pub fail_cause: Option<FailCause>,
 }
+
+/// The outcome of a [`SimulationResult`] without the simulation itself,
+/// e.g. for serializing.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulationResultView {
+	pub steps: Vec<ActionResult>,
+	pub hq_percent: u32,
+	pub success: bool,
+	pub collectability: u32,
+	pub collectable_tier: Option<usize>,
+	pub fail_cause: Option<FailCause>,
+	pub progression: u32,
+	pub quality: u32,
+	pub durability: i32,
+	pub available_cp: u32,
+	pub max_cp: u32,
+}
+impl From<&SimulationResult> for SimulationResultView {
+	fn from(value: &SimulationResult) -> Self {
+		Self {
+			steps: value.steps.clone(),
+			hq_percent: value.hq_percent,
+			success: value.success,
+			collectability: value.collectability,
+			collectable_tier: value.collectable_tier,
+			fail_cause: value.fail_cause,
+			progression: value.simulation.progression,
+			quality: value.simulation.quality,
+			durability: value.simulation.durability,
+			available_cp: value.simulation.available_cp,
+			max_cp: value.simulation.max_cp,
+		}
+	}
+}
@@ -1,4 +1,8 @@
-use crate::types::structs::CraftingLevel;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::types::structs::{Craft, CraftingLevel};
 
 pub const HQ_TABLE: [u32; 101] = [
 	1, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 6, 6, 6, 6, 7, 7, 7, 7, 8, 8, 8,
@@ -18,3 +22,165 @@ pub fn level_to_ilevel(lvl: CraftingLevel) -> u32 {
 
 	*arr.get::<usize>(Into::<u8>::into(lvl).into()).unwrap()
 }
+
+/// One row of the game's RecipeLevelTable, i.e. the values shared by every recipe of a
+/// given recipe level.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecipeLevel {
+	pub rlvl: u32,
+	pub class_job_level: CraftingLevel,
+	pub stars: u32,
+	pub progress_divider: u32,
+	pub quality_divider: u32,
+	pub progress_modifier: u32,
+	pub quality_modifier: u32,
+	pub conditions_flag: u32,
+}
+
+/// Recipe level rows keyed by rlvl.
+///
+/// [`RecipeLevelTable::builtin`] only knows the rows of its embedded sheet
+/// (`src/types/data/RecipeLevelTable.csv`); the game's full table can be
+/// [loaded](RecipeLevelTable::load) from the datamined sheet, taken from
+/// [`RecipeDatabase::recipe_level_table`](crate::recipes::RecipeDatabase::recipe_level_table),
+/// or have missing rows [inserted](RecipeLevelTable::insert).
+#[derive(Clone, Debug, Default)]
+pub struct RecipeLevelTable {
+	rows: BTreeMap<u32, RecipeLevel>,
+}
+
+impl RecipeLevelTable {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The rows of the RecipeLevelTable sheet embedded in the crate, read with
+	/// [`RecipeLevelTable::from_reader`].
+	pub fn builtin() -> &'static RecipeLevelTable {
+		static BUILTIN: OnceLock<RecipeLevelTable> = OnceLock::new();
+		BUILTIN.get_or_init(|| {
+			Self::from_reader(include_str!("data/RecipeLevelTable.csv").as_bytes())
+				.expect("the embedded RecipeLevelTable sheet is valid")
+		})
+	}
+
+	/// Adds a row, replacing any existing row with the same rlvl.
+	pub fn insert(&mut self, row: RecipeLevel) {
+		self.rows.insert(row.rlvl, row);
+	}
+
+	pub fn get(&self, rlvl: u32) -> Option<&RecipeLevel> {
+		self.rows.get(&rlvl)
+	}
+
+	pub fn rows(&self) -> impl Iterator<Item = &RecipeLevel> {
+		self.rows.values()
+	}
+
+	/// Compares a recipe's level-derived fields against this table.
+	///
+	/// Unset modifiers count as 100 and unset stars as 0, as in the simulation.
+	pub fn check(&self, recipe: &Craft) -> Result<(), RecipeLevelError> {
+		let row = self
+			.get(recipe.rlvl)
+			.ok_or(RecipeLevelError::UnknownRecipeLevel(recipe.rlvl))?;
+
+		let fields = [
+			(
+				"lvl",
+				u8::from(row.class_job_level) as f64,
+				u8::from(recipe.lvl) as f64,
+			),
+			("stars", row.stars as f64, recipe.stars.unwrap_or(0) as f64),
+			(
+				"progress_divider",
+				row.progress_divider as f64,
+				recipe.progress_divider as f64,
+			),
+			(
+				"quality_divider",
+				row.quality_divider as f64,
+				recipe.quality_divider as f64,
+			),
+			(
+				"progress_modifier",
+				row.progress_modifier as f64,
+				recipe.progress_modifier.unwrap_or(100.0),
+			),
+			(
+				"quality_modifier",
+				row.quality_modifier as f64,
+				recipe.quality_modifier.unwrap_or(100.0),
+			),
+			(
+				"conditions_flag",
+				row.conditions_flag as f64,
+				recipe.conditions_flag as f64,
+			),
+		];
+		let mismatches: Vec<_> = fields
+			.into_iter()
+			.filter(|(_, expected, actual)| expected != actual)
+			.map(|(field, expected, actual)| RecipeLevelMismatch {
+				field,
+				expected,
+				actual,
+			})
+			.collect();
+
+		if mismatches.is_empty() {
+			Ok(())
+		} else {
+			Err(RecipeLevelError::Mismatches(mismatches))
+		}
+	}
+}
+
+impl FromIterator<RecipeLevel> for RecipeLevelTable {
+	fn from_iter<T: IntoIterator<Item = RecipeLevel>>(iter: T) -> Self {
+		let mut table = Self::new();
+		for row in iter {
+			table.insert(row);
+		}
+		table
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecipeLevelMismatch {
+	/// Name of the mismatched [`Craft`] field.
+	pub field: &'static str,
+	pub expected: f64,
+	pub actual: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecipeLevelError {
+	UnknownRecipeLevel(u32),
+	Mismatches(Vec<RecipeLevelMismatch>),
+}
+
+impl fmt::Display for RecipeLevelError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			RecipeLevelError::UnknownRecipeLevel(rlvl) => {
+				write!(f, "recipe level {} is not in the table", rlvl)
+			}
+			RecipeLevelError::Mismatches(mismatches) => {
+				let messages: Vec<_> = mismatches
+					.iter()
+					.map(|mismatch| {
+						format!(
+							"{} is {} but the recipe level table has {}",
+							mismatch.field, mismatch.actual, mismatch.expected
+						)
+					})
+					.collect();
+				write!(f, "{}", messages.join("; "))
+			}
+		}
+	}
+}
+
+impl std::error::Error for RecipeLevelError {}
@@ -6,9 +6,13 @@ use crate::types::{
 };
 use dyn_clone::DynClone;
 use enum_dispatch::enum_dispatch;
+use rand::RngCore;
 
 #[enum_dispatch(CraftingActionEnum)]
 pub trait CraftingAction: DynClone {
+	/// The action's name, as shown in the English client.
+	fn get_name(&self) -> &'static str;
+
 	fn can_be_moved(&self, _current_index: u32) -> bool {
 		true
 	}
@@ -204,6 +208,10 @@ pub trait GeneralAction: CraftingAction {
 // any class that implements GeneralAction should inherit the following defaults
 /*
 impl CraftingAction for Class {
+	fn get_name(&self) -> &'static str {
+		todo!()
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		todo!()
 	}
@@ -246,6 +254,10 @@ pub trait ProgressAction: GeneralAction {}
 // any class that implements ProgressAction should inherit the following defaults
 /*
 impl CraftingAction for Class {
+	fn get_name(&self) -> &'static str {
+		todo!()
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		todo!()
 	}
@@ -320,6 +332,10 @@ pub trait QualityAction: GeneralAction {}
 // any class that implements QualityAction should inherit the following defaults
 /*
 impl CraftingAction for Class {
+	fn get_name(&self) -> &'static str {
+		todo!()
+	}
+
 	fn get_level_requirement(&self) -> (CraftingJob, CraftingLevel) {
 		todo!()
 	}
@@ -449,6 +465,10 @@ pub trait BuffAction: CraftingAction {
 // any class that implements BuffAction should inherit the following defaults
 /*
 impl CraftingAction for Class {
+	fn get_name(&self) -> &'static str {
+		todo!()
+	}
+
 	fn skip_on_fail(&self) -> bool {
 		true
 	}
@@ -492,3 +512,11 @@ impl CraftingAction for Class {
 	}
 }
 */
+
+/// A source of randomness that can drive a [`Simulation`].
+///
+/// Implemented for every cloneable [`rand::RngCore`], so any seeded generator
+/// (e.g. [`rand::rngs::StdRng`]) can be passed to [`Simulation::builder`].
+pub trait SimulationRng: RngCore + DynClone + Send {}
+impl<T: RngCore + Clone + Send> SimulationRng for T {}
+dyn_clone::clone_trait_object!(SimulationRng);
@@ -0,0 +1,2 @@
+mod project;
+pub use project::*;
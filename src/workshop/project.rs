@@ -0,0 +1,298 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::types::{
+	enums::CraftingJob,
+	structs::{Craft, CrafterStats, Ingredient},
+};
+
+/// One part of a workshop project in one of its phases, crafted a number of times.
+#[derive(Clone)]
+pub struct WorkshopStep {
+	/// The `step_id` shared by the step's ingredients, if any.
+	pub step_id: Option<String>,
+	pub part: String,
+	pub phase: u32,
+	pub recipe: Craft,
+	/// How many times the step has to be crafted to finish the phase.
+	pub repetitions: u32,
+	pub completed: u32,
+}
+
+impl WorkshopStep {
+	/// Creates the steps of a recipe whose ingredients carry `part`, `phase` and
+	/// `step_id`, as company crafts from teamcraft do: one step for each combination
+	/// of them, with the ingredients that carry it, ordered by phase and part.
+	///
+	/// Untagged ingredients make a step of phase 1 named after the recipe.
+	pub fn from_recipe(recipe: Craft, repetitions: u32) -> Vec<Self> {
+		let mut groups: BTreeMap<(u32, String, Option<String>), Vec<Ingredient>> = BTreeMap::new();
+		for ingredient in &recipe.ingredients {
+			let key = (
+				ingredient.phase.unwrap_or(1),
+				ingredient.part.clone().unwrap_or_else(|| recipe.id.clone()),
+				ingredient.step_id.clone(),
+			);
+			groups.entry(key).or_default().push(ingredient.clone());
+		}
+		if groups.is_empty() {
+			groups.insert((1, recipe.id.clone(), None), vec![]);
+		}
+
+		groups
+			.into_iter()
+			.map(|((phase, part, step_id), ingredients)| Self {
+				step_id,
+				part,
+				phase,
+				recipe: Craft {
+					ingredients,
+					..recipe.clone()
+				},
+				repetitions,
+				completed: 0,
+			})
+			.collect()
+	}
+
+	pub fn remaining(&self) -> u32 {
+		self.repetitions.saturating_sub(self.completed)
+	}
+
+	pub fn is_done(&self) -> bool {
+		self.remaining() == 0
+	}
+
+	/// Ingredients still needed for the remaining repetitions.
+	pub fn remaining_materials(&self) -> Vec<Ingredient> {
+		aggregate(self.recipe.ingredients.iter().map(|ingredient| {
+			(ingredient.id.clone(), ingredient.amount * self.remaining())
+		}))
+	}
+
+	/// Whether the crafter meets the recipe's level and stat requirements.
+	fn can_craft(&self, stats: &CrafterStats) -> bool {
+		let job = CraftingJob::from_class_job_id(self.recipe.job);
+		stats.level_for(job) >= self.recipe.lvl
+			&& stats.craftsmanship >= self.recipe.craftsmanship_req.unwrap_or(0)
+			&& stats.control >= self.recipe.control_req.unwrap_or(0)
+	}
+
+	/// Whether the crafter also meets the recipe's suggested stats.
+	fn is_comfortable(&self, stats: &CrafterStats) -> bool {
+		stats.craftsmanship >= self.recipe.suggested_craftsmanship.unwrap_or(0)
+			&& stats.control >= self.recipe.suggested_control.unwrap_or(0)
+	}
+}
+
+#[derive(Clone)]
+pub struct TeamMember {
+	pub name: String,
+	pub stats: CrafterStats,
+}
+
+/// A company workshop or restoration project: parts crafted over several phases.
+#[derive(Clone)]
+pub struct WorkshopProject {
+	pub name: String,
+	pub steps: Vec<WorkshopStep>,
+}
+
+impl WorkshopProject {
+	pub fn new(name: String, steps: Vec<WorkshopStep>) -> Self {
+		Self { name, steps }
+	}
+
+	/// Every phase of the project, in order.
+	pub fn phases(&self) -> Vec<u32> {
+		let mut phases: Vec<_> = self.steps.iter().map(|step| step.phase).collect();
+		phases.sort_unstable();
+		phases.dedup();
+		phases
+	}
+
+	/// The first phase with crafts left, if the project isn't finished.
+	pub fn current_phase(&self) -> Option<u32> {
+		self.phases()
+			.into_iter()
+			.find(|&phase| !self.is_phase_done(phase))
+	}
+
+	pub fn is_phase_done(&self, phase: u32) -> bool {
+		self.steps
+			.iter()
+			.filter(|step| step.phase == phase)
+			.all(WorkshopStep::is_done)
+	}
+
+	/// Records crafts of the step with the given part, phase and `step_id`, returning
+	/// `false` if there is no such step.
+	///
+	/// The `step_id` tells apart steps of the same part in the same phase.
+	pub fn record_craft(
+		&mut self,
+		part: &str,
+		phase: u32,
+		step_id: Option<&str>,
+		count: u32,
+	) -> bool {
+		match self.steps.iter_mut().find(|step| {
+			step.part == part && step.phase == phase && step.step_id.as_deref() == step_id
+		}) {
+			Some(step) => {
+				step.completed = (step.completed + count).min(step.repetitions);
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Fraction of all crafts done, from `0.0` to `1.0`.
+	pub fn progress(&self) -> f64 {
+		progress(self.steps.iter())
+	}
+
+	pub fn phase_progress(&self, phase: u32) -> f64 {
+		progress(self.steps.iter().filter(|step| step.phase == phase))
+	}
+
+	/// Every ingredient still needed to finish the project.
+	pub fn total_materials(&self) -> Vec<Ingredient> {
+		aggregate(
+			self.steps
+				.iter()
+				.flat_map(WorkshopStep::remaining_materials)
+				.map(|ingredient| (ingredient.id, ingredient.amount)),
+		)
+	}
+
+	/// Assigns every step to a team member and lays the project out phase by phase.
+	///
+	/// Steps go to members able to craft them, preferring those who also meet the
+	/// suggested stats, then whoever has the fewest steps so far, then the best stats.
+	/// Steps nobody can craft are left unassigned.
+	pub fn plan(&self, team: &[TeamMember]) -> Vec<PhaseChecklist> {
+		let mut assigned = vec![0; team.len()];
+		let mut phases: BTreeMap<u32, Vec<ChecklistItem>> = BTreeMap::new();
+
+		for step in &self.steps {
+			let crafter = team
+				.iter()
+				.enumerate()
+				.filter(|(_, member)| step.can_craft(&member.stats))
+				.min_by_key(|&(ix, member)| {
+					(
+						!step.is_comfortable(&member.stats),
+						assigned[ix],
+						std::cmp::Reverse(member.stats.craftsmanship + member.stats.control),
+						std::cmp::Reverse(member.stats.cp),
+					)
+				})
+				.map(|(ix, member)| {
+					if !step.is_done() {
+						assigned[ix] += 1;
+					}
+					member.name.clone()
+				});
+
+			phases.entry(step.phase).or_default().push(ChecklistItem {
+				part: step.part.clone(),
+				step_id: step.step_id.clone(),
+				crafter,
+				completed: step.completed,
+				repetitions: step.repetitions,
+				materials: step.remaining_materials(),
+			});
+		}
+
+		phases
+			.into_iter()
+			.map(|(phase, items)| PhaseChecklist { phase, items })
+			.collect()
+	}
+}
+
+#[derive(Clone)]
+pub struct ChecklistItem {
+	pub part: String,
+	pub step_id: Option<String>,
+	/// The team member who should craft this part, if anyone can.
+	pub crafter: Option<String>,
+	pub completed: u32,
+	pub repetitions: u32,
+	/// Ingredients still needed for the remaining repetitions.
+	pub materials: Vec<Ingredient>,
+}
+
+impl ChecklistItem {
+	pub fn is_done(&self) -> bool {
+		self.completed >= self.repetitions
+	}
+}
+
+#[derive(Clone)]
+pub struct PhaseChecklist {
+	pub phase: u32,
+	pub items: Vec<ChecklistItem>,
+}
+
+impl PhaseChecklist {
+	pub fn is_done(&self) -> bool {
+		self.items.iter().all(ChecklistItem::is_done)
+	}
+}
+
+impl fmt::Display for PhaseChecklist {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "Phase {}", self.phase)?;
+		for item in &self.items {
+			write!(
+				f,
+				"- [{}] {} ({}/{}) - {}",
+				if item.is_done() { "x" } else { " " },
+				item.part,
+				item.completed,
+				item.repetitions,
+				item.crafter.as_deref().unwrap_or("unassigned"),
+			)?;
+			if !item.materials.is_empty() {
+				let materials: Vec<_> = item
+					.materials
+					.iter()
+					.map(|ingredient| format!("{}x {}", ingredient.amount, ingredient.id))
+					.collect();
+				write!(f, ": {}", materials.join(", "))?;
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+}
+
+fn progress<'a>(steps: impl Iterator<Item = &'a WorkshopStep>) -> f64 {
+	let (completed, total) = steps.fold((0, 0), |(completed, total), step| {
+		(completed + step.completed.min(step.repetitions), total + step.repetitions)
+	});
+	if total == 0 {
+		1.0
+	} else {
+		completed as f64 / total as f64
+	}
+}
+
+/// Sums the amounts of each ingredient, keeping the order they first appear in and
+/// dropping those with nothing left.
+fn aggregate(ingredients: impl Iterator<Item = (String, u32)>) -> Vec<Ingredient> {
+	let mut aggregated: Vec<Ingredient> = vec![];
+	for (id, amount) in ingredients {
+		match aggregated.iter_mut().find(|ingredient| ingredient.id == id) {
+			Some(ingredient) => ingredient.amount += amount,
+			None => aggregated.push(Ingredient {
+				id,
+				amount,
+				..Default::default()
+			}),
+		}
+	}
+	aggregated.retain(|ingredient| ingredient.amount > 0);
+	aggregated
+}
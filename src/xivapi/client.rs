@@ -0,0 +1,209 @@
+use bon::bon;
+use serde_json::Value;
+use std::{fmt, fs, path::PathBuf};
+
+use crate::{
+	recipes::{sheet::Sheet, RecipeDataError, RecipeDatabase},
+	types::structs::Craft,
+};
+
+pub const DEFAULT_BASE_URL: &str = "https://v2.xivapi.com/api";
+
+/// Fetches recipes from an XIVAPI-compatible server, using its `/sheet/{sheet}/{row}`
+/// endpoints, and turns them into [`Craft`]s.
+///
+/// Rows are fetched as-is and, with a cache directory set, kept on disk so each row is
+/// only ever requested once.
+pub struct XivapiClient {
+	http: reqwest::Client,
+	base_url: String,
+	api_key: Option<String>,
+	cache_dir: Option<PathBuf>,
+}
+
+#[bon]
+impl XivapiClient {
+	/// `base_url` defaults to [`DEFAULT_BASE_URL`]; the API key, if any, is sent as the
+	/// `private_key` query parameter.
+	#[builder]
+	pub fn new(
+		base_url: Option<String>,
+		api_key: Option<String>,
+		cache_dir: Option<PathBuf>,
+	) -> Self {
+		Self {
+			http: reqwest::Client::new(),
+			base_url: base_url
+				.unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+				.trim_end_matches('/')
+				.to_string(),
+			api_key,
+			cache_dir,
+		}
+	}
+
+	/// Configures the client from `XIVAPI_BASE_URL`, `XIVAPI_KEY` and `XIVAPI_CACHE_DIR`,
+	/// read from the environment or a `.env` file.
+	pub fn from_env() -> Self {
+		dotenvy::dotenv().ok();
+		let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+		Self::builder()
+			.maybe_base_url(var("XIVAPI_BASE_URL"))
+			.maybe_api_key(var("XIVAPI_KEY"))
+			.maybe_cache_dir(var("XIVAPI_CACHE_DIR").map(PathBuf::from))
+			.build()
+	}
+
+	/// Fetches a recipe along with its RecipeLevelTable row and its items.
+	pub async fn recipe(&self, recipe_id: u32) -> Result<Craft, XivapiError> {
+		let recipe = fields(&self.row("Recipe", recipe_id).await?);
+		let link = |column: &str| {
+			recipe
+				.iter()
+				.find(|(name, _)| name == column)
+				.and_then(|(_, value)| value.parse::<u32>().ok())
+				.unwrap_or(0)
+		};
+
+		let rlvl = link("RecipeLevelTable");
+		let recipe_level = fields(&self.row("RecipeLevelTable", rlvl).await?);
+
+		let item_ids = recipe
+			.iter()
+			.filter(|(name, _)| name == "ItemResult" || name.starts_with("Ingredient["))
+			.filter_map(|(_, value)| value.parse::<u32>().ok())
+			.filter(|&item_id| item_id > 0);
+		let mut items = vec![];
+		for item_id in item_ids {
+			items.push(fields(&self.row("Item", item_id).await?));
+		}
+
+		let database = RecipeDatabase::from_sheets(
+			&Sheet::from_fields("Recipe", vec![recipe]),
+			&Sheet::from_fields("RecipeLevelTable", vec![recipe_level]),
+			&Sheet::from_fields("Item", items),
+		)?;
+		database
+			.recipe(recipe_id)
+			.cloned()
+			.ok_or(XivapiError::MissingRecipe(recipe_id))
+	}
+
+	/// Fetches a single sheet row, from the cache if possible.
+	pub async fn row(&self, sheet: &str, row_id: u32) -> Result<Value, XivapiError> {
+		let cache_path = self
+			.cache_dir
+			.as_ref()
+			.map(|dir| dir.join(sheet).join(format!("{}.json", row_id)));
+		if let Some(cached) = cache_path.as_ref().and_then(|path| fs::read(path).ok()) {
+			return Ok(serde_json::from_slice(&cached)?);
+		}
+
+		let mut request = self
+			.http
+			.get(format!("{}/sheet/{}/{}", self.base_url, sheet, row_id));
+		if let Some(api_key) = &self.api_key {
+			request = request.query(&[("private_key", api_key)]);
+		}
+		let body = request.send().await?.error_for_status()?.bytes().await?;
+		let row = serde_json::from_slice(&body)?;
+
+		if let Some(path) = cache_path {
+			if let Some(dir) = path.parent() {
+				fs::create_dir_all(dir)?;
+			}
+			fs::write(path, &body)?;
+		}
+		Ok(row)
+	}
+}
+
+/// Flattens a row's fields into `(column, value)` pairs named like the EXDSchema CSV
+/// columns: array elements become `Name[0]`, `Name[1]`, ... and links become their row id.
+fn fields(row: &Value) -> Vec<(String, String)> {
+	fn flatten(name: String, value: &Value, fields: &mut Vec<(String, String)>) {
+		match value {
+			Value::Array(values) => {
+				for (ix, value) in values.iter().enumerate() {
+					flatten(format!("{}[{}]", name, ix), value, fields);
+				}
+			}
+			Value::Object(link) => {
+				if let Some(value) = link.get("value").or_else(|| link.get("row_id")) {
+					flatten(name, value, fields);
+				}
+			}
+			Value::String(value) => fields.push((name, value.clone())),
+			Value::Null => (),
+			value => fields.push((name, value.to_string())),
+		}
+	}
+
+	let mut fields = vec![("#".to_string(), row["row_id"].to_string())];
+	if let Some(row_fields) = row["fields"].as_object() {
+		for (name, value) in row_fields {
+			flatten(name.clone(), value, &mut fields);
+		}
+	}
+	fields
+}
+
+#[derive(Debug)]
+pub enum XivapiError {
+	Http(reqwest::Error),
+	Io(std::io::Error),
+	Json(serde_json::Error),
+	Data(RecipeDataError),
+	/// The recipe row exists but doesn't describe a craftable recipe.
+	MissingRecipe(u32),
+}
+
+impl fmt::Display for XivapiError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			XivapiError::Http(err) => write!(f, "{}", err),
+			XivapiError::Io(err) => write!(f, "{}", err),
+			XivapiError::Json(err) => write!(f, "{}", err),
+			XivapiError::Data(err) => write!(f, "{}", err),
+			XivapiError::MissingRecipe(recipe_id) => {
+				write!(f, "recipe {} has no craftable data", recipe_id)
+			}
+		}
+	}
+}
+
+impl std::error::Error for XivapiError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			XivapiError::Http(err) => Some(err),
+			XivapiError::Io(err) => Some(err),
+			XivapiError::Json(err) => Some(err),
+			XivapiError::Data(err) => Some(err),
+			XivapiError::MissingRecipe(_) => None,
+		}
+	}
+}
+
+impl From<reqwest::Error> for XivapiError {
+	fn from(err: reqwest::Error) -> Self {
+		XivapiError::Http(err)
+	}
+}
+
+impl From<std::io::Error> for XivapiError {
+	fn from(err: std::io::Error) -> Self {
+		XivapiError::Io(err)
+	}
+}
+
+impl From<serde_json::Error> for XivapiError {
+	fn from(err: serde_json::Error) -> Self {
+		XivapiError::Json(err)
+	}
+}
+
+impl From<RecipeDataError> for XivapiError {
+	fn from(err: RecipeDataError) -> Self {
+		XivapiError::Data(err)
+	}
+}